@@ -0,0 +1,48 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use llms_fetch_mcp::sanitize::{SanitizeLevel, strip_chrome};
+use std::hint::black_box;
+
+/// A synthetic but representative documentation page: site nav, breadcrumb
+/// sidebar, and footer chrome around a large article body, roughly matching
+/// the shape of the real pages `strip_chrome` sees in production.
+fn representative_page(sections: usize) -> String {
+    let mut body = String::new();
+    for i in 0..sections {
+        body.push_str(&format!(
+            "<section><h2>Section {i}</h2><p>This is paragraph content for section {i}, \
+             long enough to be representative of real documentation prose rather than a \
+             one-word placeholder.</p></section>\n"
+        ));
+    }
+    format!(
+        r#"<html><head><title>Docs</title></head><body>
+        <nav>Home | Guide | API | Blog</nav>
+        <header><h1>Site Header</h1></header>
+        <div class="sidebar breadcrumbs">Home &gt; Guide &gt; Page</div>
+        <main>{body}</main>
+        <aside>Related pages</aside>
+        <footer>Copyright 2026</footer>
+        </body></html>"#
+    )
+}
+
+fn bench_strip_chrome_standard(c: &mut Criterion) {
+    let page = representative_page(500);
+    c.bench_function("strip_chrome_standard_500_sections", |b| {
+        b.iter(|| strip_chrome(black_box(&page), SanitizeLevel::Standard));
+    });
+}
+
+fn bench_strip_chrome_aggressive(c: &mut Criterion) {
+    let page = representative_page(500);
+    c.bench_function("strip_chrome_aggressive_500_sections", |b| {
+        b.iter(|| strip_chrome(black_box(&page), SanitizeLevel::Aggressive));
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_strip_chrome_standard,
+    bench_strip_chrome_aggressive
+);
+criterion_main!(benches);
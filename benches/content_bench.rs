@@ -0,0 +1,25 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use llms_fetch_mcp::content::{MarkdownCleanConfig, clean_markdown};
+use std::hint::black_box;
+
+const ASTRO_FULL: &str = include_str!("../test-fixtures/astro-llms-full.txt");
+const CONVEX_FULL: &str = include_str!("../test-fixtures/convex-llms-full.txt");
+
+// These large `llms-full.txt` dumps rarely contain any of `clean_markdown`'s
+// conversion artifacts, so the `Cow`-based short-circuiting and the shared
+// `protected_ranges` scan should make this close to a single borrowing pass
+// rather than four full-string copies plus a duplicate parse.
+fn bench_astro_full(c: &mut Criterion) {
+    c.bench_function("clean_markdown_astro_full", |b| {
+        b.iter(|| clean_markdown(black_box(ASTRO_FULL), MarkdownCleanConfig::default()));
+    });
+}
+
+fn bench_convex_full(c: &mut Criterion) {
+    c.bench_function("clean_markdown_convex_full", |b| {
+        b.iter(|| clean_markdown(black_box(CONVEX_FULL), MarkdownCleanConfig::default()));
+    });
+}
+
+criterion_group!(benches, bench_astro_full, bench_convex_full);
+criterion_main!(benches);
@@ -1,5 +1,8 @@
 use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
-use llms_fetch_mcp::toc::TocConfig;
+use llms_fetch_mcp::toc::{
+    DEFAULT_TOC_BUDGET, DEFAULT_TOC_SEPARATOR, TocConfig, extract_headings, find_optimal_level,
+    render_toc,
+};
 use std::hint::black_box;
 
 const REACT_LEARN: &str = include_str!("../test-fixtures/react-learn.txt");
@@ -94,6 +97,176 @@ fn bench_convex_full(c: &mut Criterion) {
     });
 }
 
+fn bench_extract_headings_vue_intro(c: &mut Criterion) {
+    c.bench_function("toc_extract_headings_vue_intro", |b| {
+        b.iter(|| extract_headings(black_box(VUE_INTRO), black_box(false)));
+    });
+}
+
+fn bench_extract_headings_react_learn(c: &mut Criterion) {
+    c.bench_function("toc_extract_headings_react_learn", |b| {
+        b.iter(|| extract_headings(black_box(REACT_LEARN), black_box(false)));
+    });
+}
+
+fn bench_extract_headings_python_tutorial(c: &mut Criterion) {
+    c.bench_function("toc_extract_headings_python_tutorial", |b| {
+        b.iter(|| extract_headings(black_box(PYTHON_TUTORIAL), black_box(false)));
+    });
+}
+
+fn bench_extract_headings_astro_excerpt(c: &mut Criterion) {
+    c.bench_function("toc_extract_headings_astro_excerpt", |b| {
+        b.iter(|| extract_headings(black_box(ASTRO_EXCERPT), black_box(false)));
+    });
+}
+
+fn bench_extract_headings_convex_excerpt(c: &mut Criterion) {
+    c.bench_function("toc_extract_headings_convex_excerpt", |b| {
+        b.iter(|| extract_headings(black_box(CONVEX_EXCERPT), black_box(false)));
+    });
+}
+
+fn bench_extract_headings_astro_full(c: &mut Criterion) {
+    c.bench_function("toc_extract_headings_astro_full", |b| {
+        b.iter(|| extract_headings(black_box(ASTRO_FULL), black_box(false)));
+    });
+}
+
+fn bench_extract_headings_convex_full(c: &mut Criterion) {
+    c.bench_function("toc_extract_headings_convex_full", |b| {
+        b.iter(|| extract_headings(black_box(CONVEX_FULL), black_box(false)));
+    });
+}
+
+fn bench_render_toc_vue_intro(c: &mut Criterion) {
+    let headings = extract_headings(VUE_INTRO, false);
+    let max_level = headings.iter().map(|h| h.level).max().unwrap_or(1);
+    c.bench_function("toc_render_toc_vue_intro", |b| {
+        b.iter(|| {
+            render_toc(
+                black_box(&headings),
+                black_box(max_level),
+                DEFAULT_TOC_SEPARATOR,
+                false,
+                false,
+            )
+        });
+    });
+}
+
+fn bench_render_toc_react_learn(c: &mut Criterion) {
+    let headings = extract_headings(REACT_LEARN, false);
+    let max_level = headings.iter().map(|h| h.level).max().unwrap_or(1);
+    c.bench_function("toc_render_toc_react_learn", |b| {
+        b.iter(|| {
+            render_toc(
+                black_box(&headings),
+                black_box(max_level),
+                DEFAULT_TOC_SEPARATOR,
+                false,
+                false,
+            )
+        });
+    });
+}
+
+fn bench_render_toc_python_tutorial(c: &mut Criterion) {
+    let headings = extract_headings(PYTHON_TUTORIAL, false);
+    let max_level = headings.iter().map(|h| h.level).max().unwrap_or(1);
+    c.bench_function("toc_render_toc_python_tutorial", |b| {
+        b.iter(|| {
+            render_toc(
+                black_box(&headings),
+                black_box(max_level),
+                DEFAULT_TOC_SEPARATOR,
+                false,
+                false,
+            )
+        });
+    });
+}
+
+fn bench_render_toc_astro_excerpt(c: &mut Criterion) {
+    let headings = extract_headings(ASTRO_EXCERPT, false);
+    let max_level = headings.iter().map(|h| h.level).max().unwrap_or(1);
+    c.bench_function("toc_render_toc_astro_excerpt", |b| {
+        b.iter(|| {
+            render_toc(
+                black_box(&headings),
+                black_box(max_level),
+                DEFAULT_TOC_SEPARATOR,
+                false,
+                false,
+            )
+        });
+    });
+}
+
+fn bench_render_toc_convex_excerpt(c: &mut Criterion) {
+    let headings = extract_headings(CONVEX_EXCERPT, false);
+    let max_level = headings.iter().map(|h| h.level).max().unwrap_or(1);
+    c.bench_function("toc_render_toc_convex_excerpt", |b| {
+        b.iter(|| {
+            render_toc(
+                black_box(&headings),
+                black_box(max_level),
+                DEFAULT_TOC_SEPARATOR,
+                false,
+                false,
+            )
+        });
+    });
+}
+
+fn bench_render_toc_astro_full(c: &mut Criterion) {
+    let headings = extract_headings(ASTRO_FULL, false);
+    let max_level = headings.iter().map(|h| h.level).max().unwrap_or(1);
+    c.bench_function("toc_render_toc_astro_full", |b| {
+        b.iter(|| {
+            render_toc(
+                black_box(&headings),
+                black_box(max_level),
+                DEFAULT_TOC_SEPARATOR,
+                false,
+                false,
+            )
+        });
+    });
+}
+
+fn bench_render_toc_convex_full(c: &mut Criterion) {
+    let headings = extract_headings(CONVEX_FULL, false);
+    let max_level = headings.iter().map(|h| h.level).max().unwrap_or(1);
+    c.bench_function("toc_render_toc_convex_full", |b| {
+        b.iter(|| {
+            render_toc(
+                black_box(&headings),
+                black_box(max_level),
+                DEFAULT_TOC_SEPARATOR,
+                false,
+                false,
+            )
+        });
+    });
+}
+
+fn bench_find_optimal_level(c: &mut Criterion) {
+    let headings = extract_headings(CONVEX_FULL, false);
+    c.bench_function("toc_find_optimal_level_convex_full", |b| {
+        b.iter(|| {
+            find_optimal_level(
+                black_box(&headings),
+                black_box(DEFAULT_TOC_BUDGET),
+                black_box(DEFAULT_TOC_SEPARATOR),
+                black_box(None),
+                black_box(false),
+                black_box(false),
+            )
+        });
+    });
+}
+
 fn bench_scaling(c: &mut Criterion) {
     let mut group = c.benchmark_group("toc_scaling");
 
@@ -130,6 +303,21 @@ criterion_group!(
     bench_convex_excerpt,
     bench_astro_full,
     bench_convex_full,
+    bench_extract_headings_vue_intro,
+    bench_extract_headings_react_learn,
+    bench_extract_headings_python_tutorial,
+    bench_extract_headings_astro_excerpt,
+    bench_extract_headings_convex_excerpt,
+    bench_extract_headings_astro_full,
+    bench_extract_headings_convex_full,
+    bench_render_toc_vue_intro,
+    bench_render_toc_react_learn,
+    bench_render_toc_python_tutorial,
+    bench_render_toc_astro_excerpt,
+    bench_render_toc_convex_excerpt,
+    bench_render_toc_astro_full,
+    bench_render_toc_convex_full,
+    bench_find_optimal_level,
     bench_scaling,
 );
 criterion_main!(benches);
@@ -0,0 +1,39 @@
+//! Demonstrates registering a custom `ContentConverter` on a `FetchPipeline`,
+//! for library consumers who want to plug in their own HTML-to-Markdown
+//! conversion instead of the built-in `readability`/`raw-html` converters.
+
+use std::sync::Arc;
+
+use llms_fetch_mcp::converter::{ContentConverter, ConvertedContent, FetchPipeline, RawContent};
+use llms_fetch_mcp::sanitize::{CleanConfig, SanitizeLevel};
+
+/// A trivial converter that just strips HTML tags instead of running them
+/// through Readability or html2md.
+struct StripTagsConverter;
+
+impl ContentConverter for StripTagsConverter {
+    fn convert(&self, raw: &RawContent) -> Result<ConvertedContent, String> {
+        let markdown = raw.body.replace(['<', '>'], "");
+        Ok(ConvertedContent { markdown })
+    }
+}
+
+fn main() {
+    let pipeline = FetchPipeline::builder()
+        .register("strip-tags", Arc::new(StripTagsConverter))
+        .build();
+
+    let raw = RawContent {
+        url: "https://example.com/page".to_string(),
+        content_type: "text/html".to_string(),
+        charset: None,
+        body: "<p>Hello, world!</p>".to_string(),
+        preserve_tables: false,
+        remove_selectors: CleanConfig::default().resolve(SanitizeLevel::Standard, None, None),
+        keep_admonitions: false,
+        admonition_classes: Vec::new(),
+    };
+
+    let converted = pipeline.convert(Some("strip-tags"), &raw).unwrap();
+    println!("{}", converted.markdown);
+}
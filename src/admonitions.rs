@@ -0,0 +1,180 @@
+//! Converts documentation "admonition"/"callout" boxes (`.note`, `.warning`,
+//! `.tip`, etc.) into labeled markdown blockquotes, used by
+//! `ReadabilityConverter`/`RawHtmlConverter` when
+//! `RawContent.keep_admonitions` is set. Without this, the generic
+//! converter flattens these boxes into plain paragraphs, losing the
+//! semantic cue they were meant to carry.
+
+use std::ops::Range;
+
+use regex::Regex;
+use scraper::{Html, Selector};
+
+/// Default admonition class name -> blockquote label mapping. Consulted by
+/// `FetchServer::new` to seed `admonition_classes`; not called from
+/// anywhere in this crate's library build, which only ever receives an
+/// explicit class list from its caller.
+#[allow(dead_code)]
+pub fn default_admonition_classes() -> Vec<(String, String)> {
+    [
+        ("note", "Note"),
+        ("tip", "Tip"),
+        ("warning", "Warning"),
+        ("caution", "Caution"),
+        ("important", "Important"),
+    ]
+    .into_iter()
+    .map(|(class, label)| (class.to_string(), label.to_string()))
+    .collect()
+}
+
+/// Finds the byte span of the element whose opening tag is the first
+/// occurrence of `open_tag_text` at or after `search_from`, by tracking
+/// `tag`'s open/close depth from that point, the same way
+/// `tables::find_table_spans` locates `<table>` blocks: by scanning the
+/// source rather than trusting the parser's (possibly re-serialized) byte
+/// offsets.
+fn find_element_span(
+    html: &str,
+    search_from: usize,
+    tag: &str,
+    open_tag_text: &str,
+) -> Option<Range<usize>> {
+    let start = html[search_from..].find(open_tag_text)? + search_from;
+    let pattern = format!(r"(?i)<{tag}\b[^>]*>|</{tag}\s*>");
+    let tag_re = Regex::new(&pattern).ok()?;
+
+    let mut depth = 0usize;
+    for m in tag_re.find_iter(&html[start..]) {
+        if m.as_str().starts_with("</") {
+            depth = depth.saturating_sub(1);
+            if depth == 0 {
+                return Some(start..start + m.end());
+            }
+        } else {
+            depth += 1;
+        }
+    }
+    None
+}
+
+/// Wraps `inner_html` in a `<blockquote>`, bolding `label` onto its first
+/// paragraph so `html2md` renders the whole thing as `> **Note:** ...`
+/// rather than a plain paragraph. Left as HTML (not pre-rendered markdown)
+/// so `html2md` still gets to handle any nested formatting (links, code
+/// spans) inside the admonition itself.
+fn wrap_as_blockquote(label: &str, inner_html: &str) -> String {
+    let trimmed = inner_html.trim();
+    let label_html = format!("<strong>{label}:</strong> ");
+    let body = trimmed.strip_prefix("<p>").map_or_else(
+        || format!("<p>{label_html}{trimmed}</p>"),
+        |rest| format!("<p>{label_html}{rest}"),
+    );
+    format!("<blockquote>{body}</blockquote>")
+}
+
+/// Replaces every element matching `.{class}` (for any `(class, label)` in
+/// `classes`) with a `<blockquote>` bolding `label` onto its first
+/// paragraph, so the converter's later `html2md` pass renders it as
+/// `> **{label}:** ...` instead of flattening it into a plain paragraph.
+/// An element whose span can't be re-located in `html` is left untouched
+/// rather than risking a wrong replacement.
+pub fn convert_admonitions(html: &str, classes: &[(String, String)]) -> String {
+    if classes.is_empty() {
+        return html.to_string();
+    }
+
+    let document = Html::parse_document(html);
+    let mut matches = Vec::new();
+    for (class_name, label) in classes {
+        let Ok(selector) = Selector::parse(&format!(".{class_name}")) else {
+            continue;
+        };
+        matches.extend(document.select(&selector).map(|el| (el, label.clone())));
+    }
+    matches.sort_by_key(|(el, _)| el.id());
+
+    let mut spans: Vec<(Range<usize>, String)> = Vec::new();
+    let mut search_from = 0usize;
+    for (element, label) in matches {
+        let outer = element.html();
+        let tag = element.value().name();
+        let open_tag_end = outer.find('>').map_or(outer.len(), |i| i + 1);
+        let open_tag_text = &outer[..open_tag_end];
+        if let Some(span) = find_element_span(html, search_from, tag, open_tag_text) {
+            search_from = span.end;
+            spans.push((span, label));
+        }
+    }
+
+    let mut result = String::with_capacity(html.len());
+    let mut last_end = 0usize;
+    for (span, label) in spans {
+        let original = &html[span.clone()];
+        let open_end = original.find('>').map_or(0, |i| i + 1);
+        let close_start = original.rfind("</").unwrap_or(original.len()).max(open_end);
+        let inner_html = &original[open_end..close_start];
+
+        result.push_str(&html[last_end..span.start]);
+        result.push_str(&wrap_as_blockquote(&label, inner_html));
+        last_end = span.end;
+    }
+    result.push_str(&html[last_end..]);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_converts_warning_box_to_blockquote_html() {
+        let html =
+            r#"<html><body><div class="warning"><p>Deprecated since 2.0.</p></div></body></html>"#;
+        let converted = convert_admonitions(html, &default_admonition_classes());
+        assert!(
+            converted.contains(
+                "<blockquote><p><strong>Warning:</strong> Deprecated since 2.0.</p></blockquote>"
+            ),
+            "{converted}"
+        );
+        assert!(!converted.contains(r#"class="warning""#));
+    }
+
+    #[test]
+    fn test_leaves_non_admonition_content_untouched() {
+        let html = "<html><body><p>Ordinary paragraph.</p></body></html>";
+        assert_eq!(
+            convert_admonitions(html, &default_admonition_classes()),
+            html
+        );
+    }
+
+    #[test]
+    fn test_custom_class_mapping() {
+        let html = r#"<div class="callout-danger"><p>Do not do this.</p></div>"#;
+        let classes = vec![("callout-danger".to_string(), "Danger".to_string())];
+        let converted = convert_admonitions(html, &classes);
+        assert!(converted.contains("<strong>Danger:</strong> Do not do this."));
+    }
+
+    #[test]
+    fn test_handles_multiple_admonitions_in_document_order() {
+        let html = r#"<div class="note"><p>First note.</p></div><div class="warning"><p>Then a warning.</p></div>"#;
+        let converted = convert_admonitions(html, &default_admonition_classes());
+        let note_pos = converted
+            .find("<strong>Note:</strong> First note.")
+            .unwrap();
+        let warning_pos = converted
+            .find("<strong>Warning:</strong> Then a warning.")
+            .unwrap();
+        assert!(note_pos < warning_pos);
+    }
+
+    #[test]
+    fn test_empty_class_list_is_a_no_op() {
+        let html = r#"<div class="note"><p>Untouched.</p></div>"#;
+        assert_eq!(convert_admonitions(html, &[]), html);
+    }
+}
@@ -0,0 +1,152 @@
+//! Wayback Machine fallback for dead links (`FetchInput.fallback_to_archive`).
+//!
+//! When every URL variation comes back 404/410 or a network error, the
+//! original URL is looked up against the Wayback Machine's availability
+//! API; if a snapshot exists it is fetched in place of the dead link.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Deserialize;
+
+const AVAILABILITY_API: &str = "https://archive.org/wayback/available";
+
+#[derive(Debug, Deserialize)]
+struct AvailabilityResponse {
+    archived_snapshots: ArchivedSnapshots,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ArchivedSnapshots {
+    closest: Option<ClosestSnapshot>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClosestSnapshot {
+    available: bool,
+    url: String,
+    timestamp: String,
+}
+
+/// A Wayback Machine snapshot found for a dead URL.
+pub struct Snapshot {
+    pub url: String,
+    pub timestamp: String,
+}
+
+/// Queries the Wayback Machine availability API for the closest snapshot of
+/// `url`. Returns `None` on any network/parse error or if no snapshot exists.
+pub async fn find_snapshot(client: &reqwest::Client, url: &str) -> Option<Snapshot> {
+    find_snapshot_at(client, AVAILABILITY_API, url).await
+}
+
+/// Same as `find_snapshot`, but against a caller-supplied availability
+/// endpoint; split out so tests can point it at a mock server.
+async fn find_snapshot_at(client: &reqwest::Client, api_base: &str, url: &str) -> Option<Snapshot> {
+    let response = client
+        .get(api_base)
+        .query(&[("url", url)])
+        .send()
+        .await
+        .ok()?;
+    let body: AvailabilityResponse = response.json().await.ok()?;
+    let closest = body.archived_snapshots.closest?;
+    if !closest.available {
+        return None;
+    }
+    Some(Snapshot {
+        url: closest.url,
+        timestamp: closest.timestamp,
+    })
+}
+
+static WAYBACK_TOOLBAR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?s)<!--\s*BEGIN WAYBACK TOOLBAR INSERT\s*-->.*?<!--\s*END WAYBACK TOOLBAR INSERT\s*-->",
+    )
+    .unwrap()
+});
+
+/// Strips the Wayback Machine's injected toolbar chrome (the
+/// `BEGIN/END WAYBACK TOOLBAR INSERT` comment block, including the
+/// `#wm-ipp-base` banner it wraps) from archived HTML.
+pub fn strip_wayback_chrome(html: &str) -> String {
+    WAYBACK_TOOLBAR.replace(html, "").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_wayback_chrome_removes_toolbar_block() {
+        let html = "<!-- BEGIN WAYBACK TOOLBAR INSERT -->\n<div id=\"wm-ipp-base\">banner</div>\n<!-- END WAYBACK TOOLBAR INSERT -->\n<html><body>real content</body></html>";
+        let stripped = strip_wayback_chrome(html);
+        assert!(!stripped.contains("wm-ipp-base"));
+        assert!(stripped.contains("real content"));
+    }
+
+    #[test]
+    fn test_strip_wayback_chrome_leaves_untouched_html_unchanged() {
+        let html = "<html><body>no toolbar here</body></html>";
+        assert_eq!(strip_wayback_chrome(html), html);
+    }
+
+    #[tokio::test]
+    async fn test_find_snapshot_parses_available_response() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/wayback/available"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "url": "https://docs.example.com/dead-page",
+                "archived_snapshots": {
+                    "closest": {
+                        "status": "200",
+                        "available": true,
+                        "url": "http://web.archive.org/web/20200101000000/https://docs.example.com/dead-page",
+                        "timestamp": "20200101000000"
+                    }
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let api_base = format!("{}/wayback/available", mock_server.uri());
+        let snapshot = find_snapshot_at(&client, &api_base, "https://docs.example.com/dead-page")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            snapshot.url,
+            "http://web.archive.org/web/20200101000000/https://docs.example.com/dead-page"
+        );
+        assert_eq!(snapshot.timestamp, "20200101000000");
+    }
+
+    #[tokio::test]
+    async fn test_find_snapshot_returns_none_when_unavailable() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/wayback/available"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "url": "https://docs.example.com/dead-page",
+                "archived_snapshots": {}
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let api_base = format!("{}/wayback/available", mock_server.uri());
+        let snapshot =
+            find_snapshot_at(&client, &api_base, "https://docs.example.com/dead-page").await;
+
+        assert!(snapshot.is_none());
+    }
+}
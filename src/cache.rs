@@ -0,0 +1,149 @@
+//! `.meta` sidecar files recording the HTTP response metadata behind each
+//! cached content file, used by conditional-GET revalidation, cache-stats
+//! reporting, and reconstructing a cached file's source URL.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::content_kind::ContentKind;
+
+/// Bumped alongside `Cargo.toml`'s `version` (see CLAUDE.md) so stale
+/// `.meta` files can be told apart from ones written by a newer build.
+const TOOL_VERSION: &str = "0.1.3";
+
+/// Recorded alongside a cached content file, at the same path with a
+/// `.meta` extension.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheMeta {
+    pub url: String,
+    pub final_url: String,
+    pub content_type_header: String,
+    /// `FileInfo.content_type`'s classification, recorded here too so
+    /// cache-stats and `list_cached` tooling can group files without
+    /// re-deriving it from `content_type_header` and the file extension.
+    pub content_kind: ContentKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    /// Every page's URL concatenated into this file, in order, set when this
+    /// file was built by following `<link rel="next">`/`.pagination-next`
+    /// links (see `FetchInput.follow_pagination`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination_urls: Option<Vec<String>>,
+    pub fetched_at_unix: u64,
+    pub tool_version: String,
+    /// Set by `refresh_cache` when a conditional revalidation finds the
+    /// origin now returns 404/410, so the stale file is kept (not deleted)
+    /// but flagged rather than silently treated as still current
+    #[serde(default)]
+    pub stale: bool,
+}
+
+impl CacheMeta {
+    pub fn new(
+        url: String,
+        final_url: String,
+        content_type_header: String,
+        content_kind: ContentKind,
+        etag: Option<String>,
+        last_modified: Option<String>,
+        pagination_urls: Option<Vec<String>>,
+    ) -> Self {
+        let fetched_at_unix = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self {
+            url,
+            final_url,
+            content_type_header,
+            content_kind,
+            etag,
+            last_modified,
+            pagination_urls,
+            fetched_at_unix,
+            tool_version: TOOL_VERSION.to_string(),
+            stale: false,
+        }
+    }
+}
+
+pub(crate) fn meta_path(content_path: &Path) -> std::path::PathBuf {
+    let mut meta = content_path.as_os_str().to_os_string();
+    meta.push(".meta");
+    std::path::PathBuf::from(meta)
+}
+
+/// Sidecar path for the raw, unconverted response body kept alongside
+/// `content_path` when `FetchInput.keep_raw` is set, so `reconvert` can
+/// re-run the cleaning/conversion pipeline without a network round-trip.
+pub fn raw_path(content_path: &Path, is_html: bool) -> std::path::PathBuf {
+    let mut raw = content_path.as_os_str().to_os_string();
+    raw.push(if is_html { ".raw.html" } else { ".raw.txt" });
+    std::path::PathBuf::from(raw)
+}
+
+/// Writes `meta` to `content_path`'s `.meta` sidecar via temp-file + rename,
+/// matching the atomic-write convention used for the content file itself.
+pub async fn write_cache_meta(content_path: &Path, meta: &CacheMeta) -> std::io::Result<()> {
+    let contents = serde_json::to_vec_pretty(meta)
+        .map_err(|e| std::io::Error::other(format!("serializing cache meta: {e}")))?;
+    let final_path = meta_path(content_path);
+    let temp_path = final_path.with_extension("meta.tmp");
+    fs::write(&temp_path, contents).await?;
+    fs::rename(&temp_path, &final_path).await
+}
+
+/// Reads back the `.meta` sidecar for `content_path`, if one exists and
+/// parses successfully. Consulted by `reconvert` to recover the fields
+/// needed to re-run the conversion pipeline from a `keep_raw` sidecar.
+pub async fn read_cache_meta(content_path: &Path) -> Option<CacheMeta> {
+    let bytes = fs::read(meta_path(content_path)).await.ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_write_then_read_cache_meta_round_trips() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let content_path = temp_dir.path().join("guide.html");
+        let meta = CacheMeta::new(
+            "https://example.com/guide".to_string(),
+            "https://example.com/guide/".to_string(),
+            "text/html; charset=utf-8".to_string(),
+            ContentKind::HtmlConverted,
+            Some("\"abc123\"".to_string()),
+            Some("Wed, 21 Oct 2026 07:28:00 GMT".to_string()),
+            None,
+        );
+
+        write_cache_meta(&content_path, &meta).await.unwrap();
+        let read_back = read_cache_meta(&content_path).await.unwrap();
+
+        assert_eq!(read_back.url, meta.url);
+        assert_eq!(read_back.final_url, meta.final_url);
+        assert_eq!(read_back.etag, meta.etag);
+        assert_eq!(read_back.tool_version, TOOL_VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_read_cache_meta_missing_file_returns_none() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let content_path = temp_dir.path().join("nonexistent.html");
+        assert!(read_cache_meta(&content_path).await.is_none());
+    }
+
+    #[test]
+    fn test_meta_path_appends_meta_extension() {
+        let path = Path::new("/cache/example.com/guide.html");
+        assert_eq!(
+            meta_path(path),
+            Path::new("/cache/example.com/guide.html.meta")
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Extracts a page's declared canonical URL, used by `fetch` to prefer it
+//! over the actually-requested URL as the cache path basis and
+//! `FileInfo.source_url`, so the same page served under several tracking
+//! parameters or hosts still caches to one place.
+
+use scraper::{Html, Selector};
+use url::Url;
+
+/// Scrapes `<link rel="canonical" href>` out of `html` and resolves it
+/// against `fetched_url`. Returns `None` if the tag is absent, its `href`
+/// doesn't parse, or it resolves to a different origin than `fetched_url`:
+/// a cross-origin canonical is ignored rather than trusted, since honoring
+/// it would let a page redirect where our cache writes land.
+pub fn extract_canonical_url(html: &str, fetched_url: &str) -> Option<String> {
+    let selector = Selector::parse(r#"link[rel="canonical"]"#).ok()?;
+    let document = Html::parse_document(html);
+    let href = document
+        .select(&selector)
+        .find_map(|link| link.value().attr("href"))?;
+
+    let fetched = Url::parse(fetched_url).ok()?;
+    let canonical = fetched.join(href).ok()?;
+
+    if canonical.origin() != fetched.origin() {
+        return None;
+    }
+
+    Some(canonical.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_same_origin_canonical() {
+        let html = r#"<html><head>
+            <link rel="canonical" href="https://example.com/docs/guide">
+            </head></html>"#;
+        assert_eq!(
+            extract_canonical_url(html, "https://example.com/docs/guide?utm_source=x"),
+            Some("https://example.com/docs/guide".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolves_relative_canonical_href() {
+        let html = r#"<link rel="canonical" href="/docs/guide">"#;
+        assert_eq!(
+            extract_canonical_url(html, "https://example.com/docs/guide/index.html"),
+            Some("https://example.com/docs/guide".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ignores_cross_origin_canonical() {
+        let html = r#"<link rel="canonical" href="https://other.example.com/docs/guide">"#;
+        assert_eq!(
+            extract_canonical_url(html, "https://example.com/docs/guide"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_no_canonical_tag_returns_none() {
+        let html = "<html><body><p>No canonical here.</p></body></html>";
+        assert_eq!(
+            extract_canonical_url(html, "https://example.com/page"),
+            None
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! A single `llms-fetch.toml` file (or the path named by `LLMS_FETCH_CONFIG`)
+//! collecting the tunables otherwise scattered across CLI flags, for
+//! reproducible deployments that don't want to pin down a long argv.
+//!
+//! ```toml
+//! toc_budget = 2000
+//! max_concurrent_requests = 4
+//! default_converter = "raw-html"
+//! ```
+//!
+//! Precedence, highest wins: an explicitly passed CLI flag, then an
+//! `LLMS_FETCH_*` env var named after the field (e.g.
+//! `LLMS_FETCH_TOC_BUDGET`), then this file, then the built-in default.
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// Points at the config file to load, overriding the `DEFAULT_CONFIG_FILENAME` lookup.
+pub const CONFIG_PATH_ENV_VAR: &str = "LLMS_FETCH_CONFIG";
+const DEFAULT_CONFIG_FILENAME: &str = "llms-fetch.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Config {
+    pub toc_budget: Option<usize>,
+    pub toc_threshold: Option<usize>,
+    pub toc_separator: Option<String>,
+    pub max_concurrent_requests: Option<usize>,
+    pub min_content_chars: Option<usize>,
+    pub fallback_to_archive: Option<bool>,
+    pub default_converter: Option<String>,
+    pub no_cookies: Option<bool>,
+    pub strip_inline_html_headings: Option<bool>,
+    pub default_language: Option<String>,
+    pub keep_raw: Option<bool>,
+    pub llms_full_threshold: Option<usize>,
+}
+
+impl Config {
+    /// Parses the TOML source. Errors are intended to abort startup.
+    pub fn parse(toml_source: &str) -> Result<Self, String> {
+        toml::from_str(toml_source).map_err(|e| format!("invalid config TOML: {e}"))
+    }
+
+    /// Resolves the config file named by `CONFIG_PATH_ENV_VAR`, if set
+    /// (missing is an error, since the user pointed at it explicitly), then
+    /// looks for `DEFAULT_CONFIG_FILENAME` in the working directory,
+    /// applies `LLMS_FETCH_*` env var overrides, and returns the result. A
+    /// missing default file (no env var set) is not an error — it just
+    /// means there's no file-backed config, only env vars and CLI flags.
+    pub async fn load() -> Result<Self, String> {
+        let mut config = match Self::resolve_path() {
+            Some(path) => {
+                let contents = tokio::fs::read_to_string(&path)
+                    .await
+                    .map_err(|e| format!("failed to read config {}: {e}", path.display()))?;
+                Self::parse(&contents)?
+            }
+            None => Self::default(),
+        };
+        config.apply_env_overrides();
+        Ok(config)
+    }
+
+    fn resolve_path() -> Option<PathBuf> {
+        if let Ok(path) = std::env::var(CONFIG_PATH_ENV_VAR) {
+            return Some(PathBuf::from(path));
+        }
+        let default = PathBuf::from(DEFAULT_CONFIG_FILENAME);
+        Path::new(&default).exists().then_some(default)
+    }
+
+    fn apply_env_overrides(&mut self) {
+        self.apply_env_overrides_from(|key| std::env::var(key).ok());
+    }
+
+    /// Same as `apply_env_overrides`, but taking the env lookup as a
+    /// closure so tests don't have to mutate process-wide env vars.
+    fn apply_env_overrides_from(&mut self, lookup: impl Fn(&str) -> Option<String>) {
+        if let Some(v) = lookup("LLMS_FETCH_TOC_BUDGET").and_then(|v| v.parse().ok()) {
+            self.toc_budget = Some(v);
+        }
+        if let Some(v) = lookup("LLMS_FETCH_TOC_THRESHOLD").and_then(|v| v.parse().ok()) {
+            self.toc_threshold = Some(v);
+        }
+        if let Some(v) = lookup("LLMS_FETCH_TOC_SEPARATOR") {
+            self.toc_separator = Some(v);
+        }
+        if let Some(v) = lookup("LLMS_FETCH_MAX_CONCURRENT_REQUESTS").and_then(|v| v.parse().ok()) {
+            self.max_concurrent_requests = Some(v);
+        }
+        if let Some(v) = lookup("LLMS_FETCH_MIN_CONTENT_CHARS").and_then(|v| v.parse().ok()) {
+            self.min_content_chars = Some(v);
+        }
+        if let Some(v) = lookup("LLMS_FETCH_FALLBACK_TO_ARCHIVE") {
+            self.fallback_to_archive = Some(is_truthy(&v));
+        }
+        if let Some(v) = lookup("LLMS_FETCH_DEFAULT_CONVERTER") {
+            self.default_converter = Some(v);
+        }
+        if let Some(v) = lookup("LLMS_FETCH_NO_COOKIES") {
+            self.no_cookies = Some(is_truthy(&v));
+        }
+        if let Some(v) = lookup("LLMS_FETCH_STRIP_INLINE_HTML_HEADINGS") {
+            self.strip_inline_html_headings = Some(is_truthy(&v));
+        }
+        if let Some(v) = lookup("LLMS_FETCH_DEFAULT_LANGUAGE") {
+            self.default_language = Some(v);
+        }
+        if let Some(v) = lookup("LLMS_FETCH_KEEP_RAW") {
+            self.keep_raw = Some(is_truthy(&v));
+        }
+        if let Some(v) = lookup("LLMS_FETCH_LLMS_FULL_THRESHOLD").and_then(|v| v.parse().ok()) {
+            self.llms_full_threshold = Some(v);
+        }
+    }
+}
+
+fn is_truthy(value: &str) -> bool {
+    matches!(value, "1" | "true" | "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_sample_config_fields() {
+        let config = Config::parse(
+            r#"
+            toc_budget = 2000
+            toc_threshold = 500
+            toc_separator = " - "
+            max_concurrent_requests = 4
+            min_content_chars = 50
+            fallback_to_archive = true
+            default_converter = "raw-html"
+            no_cookies = true
+            strip_inline_html_headings = true
+            default_language = "en"
+            keep_raw = true
+            llms_full_threshold = 307_200
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.toc_budget, Some(2000));
+        assert_eq!(config.toc_threshold, Some(500));
+        assert_eq!(config.toc_separator, Some(" - ".to_string()));
+        assert_eq!(config.max_concurrent_requests, Some(4));
+        assert_eq!(config.min_content_chars, Some(50));
+        assert_eq!(config.fallback_to_archive, Some(true));
+        assert_eq!(config.default_converter, Some("raw-html".to_string()));
+        assert_eq!(config.no_cookies, Some(true));
+        assert_eq!(config.strip_inline_html_headings, Some(true));
+        assert_eq!(config.default_language, Some("en".to_string()));
+        assert_eq!(config.keep_raw, Some(true));
+        assert_eq!(config.llms_full_threshold, Some(307_200));
+    }
+
+    #[test]
+    fn test_parses_empty_config_as_all_none() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn test_rejects_invalid_toml() {
+        assert!(Config::parse("not = valid = toml").is_err());
+    }
+
+    #[test]
+    fn test_env_overrides_file_values() {
+        let mut config =
+            Config::parse("toc_budget = 2000\ndefault_converter = \"readability\"").unwrap();
+
+        let env = std::collections::HashMap::from([
+            ("LLMS_FETCH_TOC_BUDGET".to_string(), "9000".to_string()),
+            (
+                "LLMS_FETCH_DEFAULT_CONVERTER".to_string(),
+                "raw-html".to_string(),
+            ),
+        ]);
+        config.apply_env_overrides_from(|key| env.get(key).cloned());
+
+        assert_eq!(config.toc_budget, Some(9000));
+        assert_eq!(config.default_converter, Some("raw-html".to_string()));
+    }
+
+    #[test]
+    fn test_env_overrides_leave_unset_fields_from_file_alone() {
+        let mut config = Config::parse("toc_threshold = 500").unwrap();
+        config.apply_env_overrides_from(|_| None);
+        assert_eq!(config.toc_threshold, Some(500));
+    }
+
+    #[test]
+    fn test_boolean_env_override_accepts_truthy_strings() {
+        let mut config = Config::default();
+        let env = std::collections::HashMap::from([(
+            "LLMS_FETCH_FALLBACK_TO_ARCHIVE".to_string(),
+            "yes".to_string(),
+        )]);
+        config.apply_env_overrides_from(|key| env.get(key).cloned());
+        assert_eq!(config.fallback_to_archive, Some(true));
+    }
+}
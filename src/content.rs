@@ -0,0 +1,590 @@
+//! Markdown post-processing applied after HTML→Markdown conversion.
+//!
+//! Rules are applied outside of code spans and fenced/indented code blocks,
+//! which are located via `pulldown-cmark` so that code examples containing
+//! `[]()`-shaped text or zero-width characters (e.g. emoji ZWJ sequences)
+//! are never touched.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use regex::Regex;
+
+/// Individually toggleable cleanup rules for `clean_markdown`.
+// Each bool is an independently-toggleable rule with no natural
+// state-machine grouping (same rationale as `Cli`/`FetchServer`/`FetchResult`
+// in src/main.rs), so a bools-to-enum refactor wouldn't remove complexity.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarkdownCleanConfig {
+    /// Remove empty anchor links like `[](#anchor)`
+    pub remove_empty_links: bool,
+    /// Remove zero-width space/joiner characters used as invisible anchors
+    pub remove_zero_width_chars: bool,
+    /// Strip trailing whitespace from every line
+    pub collapse_trailing_whitespace: bool,
+    /// Collapse runs of 3+ consecutive horizontal rules into one
+    pub collapse_repeated_hr: bool,
+    /// Convert CRLF and lone CR line endings to LF (see `normalize_line_endings`)
+    pub normalize_line_endings: bool,
+    /// End the file with exactly one `\n` (see `ensure_trailing_newline`)
+    pub ensure_trailing_newline: bool,
+}
+
+impl Default for MarkdownCleanConfig {
+    fn default() -> Self {
+        Self {
+            remove_empty_links: true,
+            remove_zero_width_chars: true,
+            collapse_trailing_whitespace: true,
+            collapse_repeated_hr: true,
+            normalize_line_endings: true,
+            ensure_trailing_newline: true,
+        }
+    }
+}
+
+static EMPTY_LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[\]\([^)]*\)").unwrap());
+static ZERO_WIDTH: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[\u{200B}\u{200C}\u{200D}\u{FEFF}]").unwrap());
+static REPEATED_HR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|\n)(?:-{3,}|\*{3,}|_{3,})(?:\n(?:-{3,}|\*{3,}|_{3,})){1,}").unwrap()
+});
+static EXCESS_BLANK_LINES: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\n{3,}").unwrap());
+
+/// Byte ranges of code spans and code blocks, within which no rule applies.
+fn protected_ranges(markdown: &str) -> Vec<std::ops::Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut code_block_start: Option<usize> = None;
+
+    for (event, range) in Parser::new_ext(markdown, Options::all()).into_offset_iter() {
+        match event {
+            Event::Code(_) => ranges.push(range),
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(_) | CodeBlockKind::Indented)) => {
+                code_block_start = Some(range.start);
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some(start) = code_block_start.take() {
+                    ranges.push(start..range.end);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    ranges
+}
+
+fn is_protected(ranges: &[std::ops::Range<usize>], pos: usize) -> bool {
+    ranges.iter().any(|r| r.contains(&pos))
+}
+
+/// Removes matches of `pattern` that fall outside a code span or code
+/// block. Borrows `markdown` unchanged (no allocation) if nothing outside
+/// protected ranges matches.
+fn apply_outside_code<'a>(
+    markdown: Cow<'a, str>,
+    ranges: &[std::ops::Range<usize>],
+    pattern: &Regex,
+) -> Cow<'a, str> {
+    let mut last_end = 0;
+    let mut result = String::new();
+    let mut matched = false;
+
+    for m in pattern.find_iter(&markdown) {
+        if is_protected(ranges, m.start()) {
+            continue;
+        }
+        if !matched {
+            result.reserve(markdown.len());
+            matched = true;
+        }
+        result.push_str(&markdown[last_end..m.start()]);
+        last_end = m.end();
+    }
+
+    if !matched {
+        return markdown;
+    }
+    result.push_str(&markdown[last_end..]);
+    Cow::Owned(result)
+}
+
+/// Strips trailing whitespace from every line. Borrows `markdown` unchanged
+/// (no allocation) if no line has any.
+fn collapse_trailing_whitespace(markdown: Cow<'_, str>) -> Cow<'_, str> {
+    if markdown.lines().all(|line| line == line.trim_end()) {
+        return markdown;
+    }
+    Cow::Owned(
+        markdown
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+/// Converts CRLF and lone CR line endings to LF. Deliberately not aware of
+/// code fences, unlike the other rules in this module: a CR byte inside a
+/// code block is essentially never semantically meaningful for fetched
+/// documentation, so unlike a visible character that might appear in prose
+/// example text, this is safe to apply unconditionally. Borrows `content`
+/// unchanged (no allocation) if it contains no `\r`.
+pub fn normalize_line_endings(content: &str) -> Cow<'_, str> {
+    if !content.contains('\r') {
+        return Cow::Borrowed(content);
+    }
+    Cow::Owned(content.replace("\r\n", "\n").replace('\r', "\n"))
+}
+
+/// Ends `content` with exactly one `\n`: appends one if missing, trims extras
+/// down to one if there are several. Borrows `content` unchanged (no
+/// allocation) if it already ends with exactly one.
+pub fn ensure_trailing_newline(content: &str) -> Cow<'_, str> {
+    if content.is_empty() {
+        return Cow::Borrowed(content);
+    }
+    let trimmed = content.trim_end_matches('\n');
+    if trimmed.len() == content.len() - 1 {
+        return Cow::Borrowed(content);
+    }
+    Cow::Owned(format!("{trimmed}\n"))
+}
+
+/// Cleans HTML→Markdown conversion artifacts: empty anchor links, invisible
+/// anchor characters, trailing whitespace, and repeated horizontal rules.
+/// Never modifies content inside code spans or code blocks. Each rule is a
+/// no-op allocation-wise when it finds nothing to change, so documents
+/// without conversion artifacts cost at most a few scans rather than
+/// several full-string copies. The empty-link and zero-width passes also
+/// share one `protected_ranges` scan when the first pass leaves `markdown`
+/// untouched, rather than re-parsing it for each pass.
+pub fn clean_markdown(markdown: &str, config: MarkdownCleanConfig) -> String {
+    let mut result = Cow::Borrowed(markdown);
+    let mut ranges = None;
+
+    if config.normalize_line_endings && result.contains('\r') {
+        result = Cow::Owned(normalize_line_endings(&result).into_owned());
+    }
+    if config.remove_empty_links {
+        let current_ranges = ranges.unwrap_or_else(|| protected_ranges(&result));
+        result = apply_outside_code(result, &current_ranges, &EMPTY_LINK);
+        ranges = (!matches!(result, Cow::Owned(_))).then_some(current_ranges);
+    }
+    if config.remove_zero_width_chars {
+        let current_ranges = ranges.unwrap_or_else(|| protected_ranges(&result));
+        result = apply_outside_code(result, &current_ranges, &ZERO_WIDTH);
+    }
+    if config.collapse_repeated_hr
+        && let Cow::Owned(s) = REPEATED_HR.replace_all(&result, "\n---")
+    {
+        result = Cow::Owned(s);
+    }
+    if config.collapse_trailing_whitespace {
+        result = collapse_trailing_whitespace(result);
+    }
+    if config.ensure_trailing_newline {
+        result = Cow::Owned(ensure_trailing_newline(&result).into_owned());
+    }
+
+    result.into_owned()
+}
+
+/// Extra whitespace cleanup beyond `clean_markdown`: strips trailing
+/// whitespace from every line and collapses any run of blank lines down to
+/// a single blank line. Kept as a separate, opt-in pass (rather than folded
+/// into `clean_markdown`) so the default output doesn't change for existing
+/// callers.
+pub fn normalize_whitespace_markdown(markdown: &str) -> String {
+    let trimmed = collapse_trailing_whitespace(Cow::Borrowed(markdown));
+    EXCESS_BLANK_LINES
+        .replace_all(&trimmed, "\n\n")
+        .into_owned()
+}
+
+/// Broader than `clean_markdown`'s default `remove_empty_links`: the link
+/// text may also be pure whitespace or a zero-width character, not just
+/// completely empty. Documentation generators like Astro and React's commonly
+/// emit invisible heading anchors (e.g. `<a aria-hidden="true" id="top"> </a>`)
+/// that `html2md` converts to `[ ](top)` or `[\u{200b}](top)` rather than the
+/// plain `[](top)` the default cleanup catches.
+static ANCHOR_LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[[\s\u{200B}\u{200C}\u{200D}\u{FEFF}]*\]\([^)]*\)").unwrap());
+
+/// Strips anchor-only links whose visible text is empty, whitespace, or a
+/// zero-width character (see `ANCHOR_LINK`). Kept as a separate, opt-in pass
+/// (rather than folded into `clean_markdown`'s default `remove_empty_links`)
+/// since it's a superset broad enough to also remove a link whose text is a
+/// single space by design.
+pub fn strip_anchor_links(markdown: &str) -> String {
+    let ranges = protected_ranges(markdown);
+    apply_outside_code(Cow::Borrowed(markdown), &ranges, &ANCHOR_LINK).into_owned()
+}
+
+/// Curly quotes, non-breaking/thin/narrow-no-break spaces, and soft hyphens
+/// that `html2md` carries over verbatim from styled prose, which break
+/// exact-text search and inflate token counts slightly. Em dashes and
+/// ellipses are deliberately left out: unlike a quote or a layout space,
+/// they're often intentional typography rather than a conversion artifact.
+static TYPOGRAPHY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"[\u{2018}\u{2019}\u{201A}\u{201B}\u{201C}\u{201D}\u{201E}\u{201F}\u{00A0}\u{2009}\u{202F}\u{00AD}]",
+    )
+    .unwrap()
+});
+
+fn typography_replacement(c: char) -> &'static str {
+    match c {
+        '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => "'",
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => "\"",
+        '\u{00A0}' | '\u{2009}' | '\u{202F}' => " ",
+        '\u{00AD}' => "",
+        _ => unreachable!("TYPOGRAPHY regex and typography_replacement must stay in sync"),
+    }
+}
+
+/// Normalizes typographic artifacts that `html2md` carries over verbatim
+/// from styled prose: curly quotes to straight quotes, non-breaking/thin
+/// spaces to regular spaces, soft hyphens removed (see `TYPOGRAPHY`).
+/// Applied outside code spans and code blocks, like the rest of this
+/// module's rules, so a docs page about NBSP that shows one in a code
+/// example is left untouched. Opt-in via `FetchInput.normalize_typography`
+/// or the server's `LLMS_FETCH_NORMALIZE_TYPOGRAPHY` default, rather than
+/// folded into `clean_markdown`, so existing callers' output doesn't change.
+pub fn normalize_typography(markdown: &str) -> String {
+    let ranges = protected_ranges(markdown);
+    let mut result = String::with_capacity(markdown.len());
+    let mut last_end = 0;
+
+    for m in TYPOGRAPHY.find_iter(markdown) {
+        if is_protected(&ranges, m.start()) {
+            continue;
+        }
+        result.push_str(&markdown[last_end..m.start()]);
+        result.push_str(typography_replacement(
+            m.as_str().chars().next().expect("regex match is non-empty"),
+        ));
+        last_end = m.end();
+    }
+    result.push_str(&markdown[last_end..]);
+    result
+}
+
+/// Minimum token length and common English stop words `top_keywords` filters
+/// out, tuned for picking up domain-specific terms rather than prose filler
+const TOP_KEYWORDS_MIN_LENGTH: usize = 4;
+const TOP_KEYWORDS_STOP_WORDS: &[&str] = &[
+    "that", "this", "with", "from", "your", "have", "more", "will", "been", "than", "then", "them",
+    "they", "what", "when", "where", "which", "while", "about", "into", "their", "there", "these",
+    "those", "would", "could", "should", "also", "each", "such", "only", "same", "some", "other",
+    "over", "does", "doing", "here", "just", "like", "most", "must", "very", "html",
+];
+
+/// Tokenizes `content` by whitespace, lowercases each token and strips
+/// leading/trailing punctuation, then counts occurrences of tokens at least
+/// `min_length` characters long that aren't in `stop_words`. Used to build
+/// simple keyword extraction without a heavy NLP dependency (see
+/// `top_keywords`).
+pub fn word_frequency_map(
+    content: &str,
+    min_length: usize,
+    stop_words: &[&str],
+) -> HashMap<String, usize> {
+    let mut frequencies = HashMap::new();
+    for token in content.split_whitespace() {
+        let word = token
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        if word.chars().count() < min_length || stop_words.contains(&word.as_str()) {
+            continue;
+        }
+        *frequencies.entry(word).or_insert(0) += 1;
+    }
+    frequencies
+}
+
+/// Returns the `n` most frequent words in `content` by `word_frequency_map`,
+/// filtered through `TOP_KEYWORDS_STOP_WORDS`, ties broken alphabetically for
+/// deterministic output.
+pub fn top_keywords(content: &str, n: usize) -> Vec<(String, usize)> {
+    let frequencies = word_frequency_map(content, TOP_KEYWORDS_MIN_LENGTH, TOP_KEYWORDS_STOP_WORDS);
+    let mut keywords: Vec<(String, usize)> = frequencies.into_iter().collect();
+    keywords.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    keywords.truncate(n);
+    keywords
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_config() -> MarkdownCleanConfig {
+        MarkdownCleanConfig::default()
+    }
+
+    #[test]
+    fn test_removes_empty_anchor_link() {
+        let md = "## Heading [](#heading)\n\nBody text.";
+        let cleaned = clean_markdown(md, default_config());
+        assert_eq!(cleaned, "## Heading\n\nBody text.\n");
+    }
+
+    #[test]
+    fn test_preserves_empty_link_inside_code_span() {
+        let md = "Use `[]()` as a literal empty link example.";
+        let cleaned = clean_markdown(md, default_config());
+        assert_eq!(cleaned, format!("{md}\n"));
+    }
+
+    #[test]
+    fn test_preserves_zero_width_joiner_in_code_block() {
+        let md = "```\nfamily emoji uses \u{200D} as a joiner\n```";
+        let cleaned = clean_markdown(md, default_config());
+        assert_eq!(cleaned, format!("{md}\n"));
+    }
+
+    #[test]
+    fn test_removes_zero_width_space_outside_code() {
+        let md = "Hello\u{200B}World";
+        let cleaned = clean_markdown(md, default_config());
+        assert_eq!(cleaned, "HelloWorld\n");
+    }
+
+    #[test]
+    fn test_collapses_trailing_whitespace() {
+        let md = "line one   \nline two\t\n";
+        let cleaned = clean_markdown(md, default_config());
+        assert_eq!(cleaned, "line one\nline two\n");
+    }
+
+    #[test]
+    fn test_rules_individually_toggleable() {
+        let md = "Hello\u{200B}World [](#anchor)";
+        let config = MarkdownCleanConfig {
+            remove_empty_links: false,
+            remove_zero_width_chars: true,
+            collapse_trailing_whitespace: false,
+            collapse_repeated_hr: false,
+            normalize_line_endings: false,
+            ensure_trailing_newline: false,
+        };
+        let cleaned = clean_markdown(md, config);
+        assert_eq!(cleaned, "HelloWorld [](#anchor)");
+    }
+
+    #[test]
+    fn test_normalizes_crlf_and_lone_cr_to_lf() {
+        let md = "line one\r\nline two\rline three\n";
+        let cleaned = clean_markdown(md, default_config());
+        assert_eq!(cleaned, "line one\nline two\nline three\n");
+    }
+
+    #[test]
+    fn test_normalize_line_endings_toggleable() {
+        let md = "line one\r\nline two";
+        let config = MarkdownCleanConfig {
+            normalize_line_endings: false,
+            ..default_config()
+        };
+        let cleaned = clean_markdown(md, config);
+        assert_eq!(cleaned, format!("{md}\n"));
+    }
+
+    #[test]
+    fn test_ensures_trailing_newline_when_missing() {
+        let md = "line one\nline two";
+        let cleaned = clean_markdown(md, default_config());
+        assert_eq!(cleaned, "line one\nline two\n");
+    }
+
+    #[test]
+    fn test_ensures_single_trailing_newline_when_several() {
+        let md = "line one\nline two\n\n\n\n";
+        let cleaned = clean_markdown(md, default_config());
+        assert_eq!(cleaned, "line one\nline two\n");
+    }
+
+    #[test]
+    fn test_ensure_trailing_newline_toggleable() {
+        let md = "line one\nline two";
+        let config = MarkdownCleanConfig {
+            ensure_trailing_newline: false,
+            ..default_config()
+        };
+        let cleaned = clean_markdown(md, config);
+        assert_eq!(cleaned, md);
+    }
+
+    #[test]
+    fn test_normalize_whitespace_collapses_multiple_blank_lines() {
+        let md = "line one\n\n\n\n\nline two";
+        let normalized = normalize_whitespace_markdown(md);
+        assert_eq!(normalized, "line one\n\nline two");
+    }
+
+    #[test]
+    fn test_normalize_whitespace_strips_trailing_spaces() {
+        let md = "line one   \nline two\t\n";
+        let normalized = normalize_whitespace_markdown(md);
+        assert_eq!(normalized, "line one\nline two");
+    }
+
+    #[test]
+    fn test_normalize_whitespace_leaves_single_blank_line_unchanged() {
+        let md = "line one\n\nline two";
+        assert_eq!(normalize_whitespace_markdown(md), md);
+    }
+
+    #[test]
+    fn test_strip_anchor_links_removes_whitespace_text_link() {
+        // `html2md` output for an Astro-style `<a aria-hidden="true" id="top"> </a>`
+        let md = "Heading [ ](#getting-started) trailing.";
+        assert_eq!(strip_anchor_links(md), "Heading  trailing.");
+    }
+
+    #[test]
+    fn test_strip_anchor_links_removes_zero_width_text_link() {
+        // `html2md` output for a React-style `<a id="top">\u{200b}</a>`
+        let md = "Heading [\u{200B}](top) trailing.";
+        assert_eq!(strip_anchor_links(md), "Heading  trailing.");
+    }
+
+    #[test]
+    fn test_strip_anchor_links_still_removes_plain_empty_link() {
+        let md = "Heading [](#heading) trailing.";
+        assert_eq!(strip_anchor_links(md), "Heading  trailing.");
+    }
+
+    #[test]
+    fn test_strip_anchor_links_preserves_link_with_real_text() {
+        let md = "See [the guide](#guide) for details.";
+        assert_eq!(strip_anchor_links(md), md);
+    }
+
+    #[test]
+    fn test_strip_anchor_links_preserves_whitespace_link_in_code_span() {
+        let md = "Use `[ ]()` as a literal example.";
+        assert_eq!(strip_anchor_links(md), md);
+    }
+
+    #[test]
+    fn test_normalize_typography_straightens_quotes_and_spaces() {
+        let md = "\u{2018}Hello\u{2019} \u{201C}World\u{201D}\u{00A0}pre\u{00AD}fix";
+        assert_eq!(normalize_typography(md), "'Hello' \"World\" prefix");
+    }
+
+    #[test]
+    fn test_normalize_typography_preserves_em_dash_and_ellipsis() {
+        let md = "wait\u{2014}really\u{2026}";
+        assert_eq!(normalize_typography(md), md);
+    }
+
+    #[test]
+    fn test_normalize_typography_preserves_nbsp_in_code_span() {
+        let md = "A docs page about `a\u{00A0}b` non-breaking spaces.";
+        assert_eq!(normalize_typography(md), md);
+    }
+
+    #[test]
+    fn test_normalize_typography_preserves_nbsp_in_code_block() {
+        let md = "```\na\u{00A0}b\n```";
+        assert_eq!(normalize_typography(md), md);
+    }
+
+    /// Unoptimized four-full-pass reference implementation of `clean_markdown`,
+    /// kept only in this test to pin the optimized `Cow`-based version to
+    /// byte-identical output.
+    fn reference_clean_markdown(markdown: &str, config: MarkdownCleanConfig) -> String {
+        let mut result = markdown.to_string();
+
+        if config.normalize_line_endings {
+            result = result.replace("\r\n", "\n").replace('\r', "\n");
+        }
+        if config.remove_empty_links {
+            result = reference_apply_outside_code(&result, &protected_ranges(&result), &EMPTY_LINK);
+        }
+        if config.remove_zero_width_chars {
+            result = reference_apply_outside_code(&result, &protected_ranges(&result), &ZERO_WIDTH);
+        }
+        if config.collapse_repeated_hr {
+            result = REPEATED_HR.replace_all(&result, "\n---").into_owned();
+        }
+        if config.collapse_trailing_whitespace {
+            result = result
+                .lines()
+                .map(str::trim_end)
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+        if config.ensure_trailing_newline && !result.is_empty() {
+            let trimmed = result.trim_end_matches('\n');
+            result = format!("{trimmed}\n");
+        }
+
+        result
+    }
+
+    fn reference_apply_outside_code(
+        markdown: &str,
+        ranges: &[std::ops::Range<usize>],
+        pattern: &Regex,
+    ) -> String {
+        let mut result = String::with_capacity(markdown.len());
+        let mut last_end = 0;
+
+        for m in pattern.find_iter(markdown) {
+            if is_protected(ranges, m.start()) {
+                continue;
+            }
+            result.push_str(&markdown[last_end..m.start()]);
+            last_end = m.end();
+        }
+        result.push_str(&markdown[last_end..]);
+        result
+    }
+
+    #[test]
+    fn test_word_frequency_map_counts_tokens_above_min_length() {
+        let frequencies = word_frequency_map("Rust rust RUST go go", 3, &[]);
+        assert_eq!(frequencies.get("rust"), Some(&3));
+        assert_eq!(frequencies.get("go"), None);
+    }
+
+    #[test]
+    fn test_word_frequency_map_strips_punctuation_and_skips_stop_words() {
+        let frequencies = word_frequency_map("Rust, rust! (rust) the the the", 3, &["the"]);
+        assert_eq!(frequencies.get("rust"), Some(&3));
+        assert_eq!(frequencies.get("the"), None);
+    }
+
+    #[test]
+    fn test_top_keywords_orders_by_frequency_then_alphabetically() {
+        let keywords = top_keywords("widget widget gadget gadget sprocket", 2);
+        assert_eq!(
+            keywords,
+            vec![("gadget".to_string(), 2), ("widget".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_top_keywords_truncates_to_n() {
+        let keywords = top_keywords("alpha beta gamma delta", 2);
+        assert_eq!(keywords.len(), 2);
+    }
+
+    #[test]
+    fn test_clean_markdown_matches_reference_on_large_fixtures() {
+        let astro_full = include_str!("../test-fixtures/astro-llms-full.txt");
+        let convex_full = include_str!("../test-fixtures/convex-llms-full.txt");
+
+        for fixture in [astro_full, convex_full] {
+            assert_eq!(
+                clean_markdown(fixture, default_config()),
+                reference_clean_markdown(fixture, default_config())
+            );
+        }
+    }
+}
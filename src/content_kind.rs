@@ -0,0 +1,241 @@
+//! Stable, machine-readable classification for `FileInfo.content_type` and
+//! `CacheMeta.content_kind`, replacing ad-hoc substring matching on the
+//! fetched URL (which mislabels a page like `/docs/llms.txt.html` as an
+//! `llms.txt` file just because the path contains that substring).
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Classification of a cached file's content. Serializes as the same
+/// kebab-case strings `content_type` already used as an ad-hoc `String`,
+/// so existing consumers matching on e.g. `"markdown"` or `"html-converted"`
+/// keep working unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContentKind {
+    LlmsFull,
+    Llms,
+    Markdown,
+    HtmlConverted,
+    /// The original, unconverted HTML body kept alongside the converted
+    /// markdown when `FetchInput.include_raw_html` is set, for auditing the
+    /// conversion. Never returned by `classify` itself — assigned directly
+    /// by `fetch_impl` when it writes the raw sidecar file
+    HtmlRaw,
+    Text,
+    Json,
+    Feed,
+    Pdf,
+    PdfExtracted,
+    GithubListing,
+}
+
+/// A fetch outcome that bypasses the usual content-type/URL classification
+/// entirely - a synthesized result rather than a downloaded document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructuralOutcome {
+    /// Downloaded and (if the `pdf` feature is enabled) text-extracted
+    Pdf,
+    /// Synthesized GitHub directory listing (see `try_github_listing_fallback`)
+    GithubListing,
+    /// An ordinarily fetched and converted document
+    None,
+}
+
+impl ContentKind {
+    /// Classifies a fetch result. Checked in this order: `structural`
+    /// outcomes (a synthesized GitHub listing, or a PDF) always win; then
+    /// the exact final path segment (not a substring match anywhere in the
+    /// URL) for the `llms.txt`/`llms-full.txt` convention; then the
+    /// already-determined `is_markdown`/`is_html` outcome; finally a
+    /// content-type sniff for JSON/feed bodies, falling back to `Text`.
+    pub fn classify(
+        final_url: &str,
+        content_type_header: &str,
+        is_html: bool,
+        is_markdown: bool,
+        structural: StructuralOutcome,
+    ) -> Self {
+        match structural {
+            StructuralOutcome::Pdf => {
+                return if cfg!(feature = "pdf") {
+                    Self::PdfExtracted
+                } else {
+                    Self::Pdf
+                };
+            }
+            StructuralOutcome::GithubListing => return Self::GithubListing,
+            StructuralOutcome::None => {}
+        }
+
+        let path = final_url.split(['?', '#']).next().unwrap_or(final_url);
+        let last_segment = path.rsplit('/').next().unwrap_or(path);
+
+        if last_segment.eq_ignore_ascii_case("llms-full.txt") {
+            return Self::LlmsFull;
+        }
+        if last_segment.eq_ignore_ascii_case("llms.txt") {
+            return Self::Llms;
+        }
+
+        if is_markdown {
+            return Self::Markdown;
+        }
+        if is_html {
+            return Self::HtmlConverted;
+        }
+
+        let content_type_header = content_type_header.to_lowercase();
+        if content_type_header.contains("application/json") || content_type_header.contains("+json")
+        {
+            return Self::Json;
+        }
+        if content_type_header.contains("application/rss+xml")
+            || content_type_header.contains("application/atom+xml")
+            || content_type_header.contains("application/feed+json")
+        {
+            return Self::Feed;
+        }
+
+        Self::Text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Case {
+        final_url: &'static str,
+        content_type_header: &'static str,
+        is_html: bool,
+        is_markdown: bool,
+        structural: StructuralOutcome,
+        expected: ContentKind,
+    }
+
+    #[test]
+    fn test_classification_table() {
+        let cases = [
+            Case {
+                final_url: "https://example.com/docs/llms-full.txt",
+                content_type_header: "text/plain",
+                is_html: false,
+                is_markdown: false,
+                structural: StructuralOutcome::None,
+                expected: ContentKind::LlmsFull,
+            },
+            Case {
+                final_url: "https://example.com/docs/llms.txt",
+                content_type_header: "text/plain",
+                is_html: false,
+                is_markdown: false,
+                structural: StructuralOutcome::None,
+                expected: ContentKind::Llms,
+            },
+            Case {
+                final_url: "https://example.com/docs/llms.txt.html",
+                content_type_header: "text/html",
+                is_html: true,
+                is_markdown: false,
+                structural: StructuralOutcome::None,
+                expected: ContentKind::HtmlConverted,
+            },
+            Case {
+                final_url: "https://example.com/guide",
+                content_type_header: "text/markdown",
+                is_html: false,
+                is_markdown: true,
+                structural: StructuralOutcome::None,
+                expected: ContentKind::Markdown,
+            },
+            Case {
+                final_url: "https://example.com/guide",
+                content_type_header: "text/html",
+                is_html: true,
+                is_markdown: false,
+                structural: StructuralOutcome::None,
+                expected: ContentKind::HtmlConverted,
+            },
+            Case {
+                final_url: "https://example.com/guide.pdf",
+                content_type_header: "application/pdf",
+                is_html: false,
+                is_markdown: false,
+                structural: StructuralOutcome::Pdf,
+                expected: if cfg!(feature = "pdf") {
+                    ContentKind::PdfExtracted
+                } else {
+                    ContentKind::Pdf
+                },
+            },
+            Case {
+                final_url: "https://github.com/example/repo/tree/main/docs",
+                content_type_header: "text/html",
+                is_html: true,
+                is_markdown: false,
+                structural: StructuralOutcome::GithubListing,
+                expected: ContentKind::GithubListing,
+            },
+            Case {
+                final_url: "https://example.com/api/data",
+                content_type_header: "application/json; charset=utf-8",
+                is_html: false,
+                is_markdown: false,
+                structural: StructuralOutcome::None,
+                expected: ContentKind::Json,
+            },
+            Case {
+                final_url: "https://example.com/feed",
+                content_type_header: "application/rss+xml",
+                is_html: false,
+                is_markdown: false,
+                structural: StructuralOutcome::None,
+                expected: ContentKind::Feed,
+            },
+            Case {
+                final_url: "https://example.com/raw-data",
+                content_type_header: "application/octet-stream",
+                is_html: false,
+                is_markdown: false,
+                structural: StructuralOutcome::None,
+                expected: ContentKind::Text,
+            },
+        ];
+
+        for case in cases {
+            let actual = ContentKind::classify(
+                case.final_url,
+                case.content_type_header,
+                case.is_html,
+                case.is_markdown,
+                case.structural,
+            );
+            assert_eq!(
+                actual, case.expected,
+                "classifying {} (content-type {})",
+                case.final_url, case.content_type_header
+            );
+        }
+    }
+
+    #[test]
+    fn test_serializes_as_existing_kebab_case_strings() {
+        assert_eq!(
+            serde_json::to_string(&ContentKind::LlmsFull).unwrap(),
+            "\"llms-full\""
+        );
+        assert_eq!(
+            serde_json::to_string(&ContentKind::HtmlConverted).unwrap(),
+            "\"html-converted\""
+        );
+        assert_eq!(
+            serde_json::to_string(&ContentKind::GithubListing).unwrap(),
+            "\"github-listing\""
+        );
+        assert_eq!(
+            serde_json::to_string(&ContentKind::HtmlRaw).unwrap(),
+            "\"html-raw\""
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Heuristic detection of low-quality content for `FetchOutput.files`.
+//!
+//! A common silent failure mode: a JS-rendered single-page app returns a
+//! near-empty shell document, Readability/`html2md` dutifully extract the
+//! handful of words of "Loading…" boilerplate that made it into the initial
+//! response, and the caller reads a useless file without realizing it. This
+//! module flags that case on `FileInfo.warning` so it's visible in the
+//! output rather than silently treated as a successful fetch.
+//!
+//! A related but distinct failure mode: an agent hands the tool a
+//! homepage/marketing URL instead of a docs page, and gets back
+//! cleaned-but-useless hero text and calls-to-action. `detect_not_docs`
+//! flags that on `FileInfo.likely_not_docs`.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Below this word count, a converted page is treated as too short to trust
+/// on its own; combined with either signal below it's treated as a shell.
+const MIN_CONFIDENT_WORDS: usize = 40;
+/// Fraction of the raw HTML's bytes that sit inside `<script>` tags above
+/// which a short page is treated as script-driven rather than content-driven.
+const HIGH_SCRIPT_RATIO: f64 = 0.5;
+
+const JS_REQUIRED_MARKERS: &[&str] = &[
+    "enable javascript",
+    "enable js",
+    "javascript is required",
+    "requires javascript",
+    "please enable javascript",
+];
+
+pub const SPA_SHELL_WARNING: &str =
+    "page appears to require JavaScript rendering; content may be incomplete";
+
+static SCRIPT_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<script\b[^>]*>.*?</script>").unwrap());
+
+/// Fraction of `html`'s bytes that fall inside `<script>...</script>` tags.
+fn script_ratio(html: &str) -> f64 {
+    if html.is_empty() {
+        return 0.0;
+    }
+    let script_bytes: usize = SCRIPT_TAG.find_iter(html).map(|m| m.as_str().len()).sum();
+    #[allow(clippy::cast_precision_loss)]
+    {
+        script_bytes as f64 / html.len() as f64
+    }
+}
+
+/// Returns `Some(SPA_SHELL_WARNING)` if `markdown` (the converted content)
+/// looks like it came from a JS-rendered shell rather than real content:
+/// either the raw HTML explicitly tells the reader to enable JavaScript, or
+/// the page is both suspiciously short and dominated by script bytes. Longer
+/// pages are never flagged, so a genuinely short page (e.g. a small
+/// `llms.txt`) isn't penalized just for brevity.
+pub fn detect_spa_shell(raw_html: &str, markdown: &str) -> Option<&'static str> {
+    let word_count = markdown.split_whitespace().count();
+    if word_count >= MIN_CONFIDENT_WORDS {
+        return None;
+    }
+
+    let lower = raw_html.to_lowercase();
+    let mentions_js_required = JS_REQUIRED_MARKERS.iter().any(|m| lower.contains(m));
+    if mentions_js_required || script_ratio(raw_html) > HIGH_SCRIPT_RATIO {
+        return Some(SPA_SHELL_WARNING);
+    }
+
+    None
+}
+
+/// Below this word count, there isn't enough text to judge doc-likeness
+/// reliably; `detect_spa_shell`'s short-page handling already covers this range.
+const MIN_WORDS_FOR_DOC_LIKENESS: usize = 60;
+/// Average words per non-empty line below which content reads as
+/// nav links/buttons/taglines rather than prose paragraphs.
+const MIN_WORDS_PER_LINE: f64 = 6.0;
+
+/// Returns `true` when `markdown` (the converted content) shows none of the
+/// three signals a documentation page almost always has: an ATX heading, a
+/// fenced code block, or prose-dense paragraphs (as opposed to the short,
+/// link-heavy lines of a homepage's hero section and nav). A conservative
+/// heuristic hint for `FileInfo.likely_not_docs`, not a hard failure — any
+/// one signal present is enough to call it docs.
+pub fn detect_not_docs(markdown: &str) -> bool {
+    let word_count = markdown.split_whitespace().count();
+    if word_count < MIN_WORDS_FOR_DOC_LIKENESS {
+        return false;
+    }
+
+    let has_heading = markdown
+        .lines()
+        .any(|line| line.trim_start().starts_with('#'));
+    let has_code_block = markdown.contains("```");
+
+    let non_empty_lines = markdown.lines().filter(|l| !l.trim().is_empty()).count();
+    #[allow(clippy::cast_precision_loss)]
+    let words_per_line = word_count as f64 / non_empty_lines.max(1) as f64;
+    let reasonable_density = words_per_line >= MIN_WORDS_PER_LINE;
+
+    !has_heading && !has_code_block && !reasonable_density
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPA_SHELL_HTML: &str = r#"<html><head>
+            <script src="/static/js/main.a1b2c3.js"></script>
+            <script>console.log('boot');window.__INITIAL_STATE__={};</script>
+        </head><body>
+            <div id="root"><noscript>You need to enable JavaScript to run this app.</noscript></div>
+            <script>window.App && window.App.render(document.getElementById('root'));</script>
+        </body></html>"#;
+
+    const REAL_CONTENT_HTML: &str = r"<html><body><article>
+            <h1>Getting Started</h1>
+            <p>This guide walks through installing the toolkit, configuring your
+            first project, and running the development server. It covers the
+            core concepts you need before moving on to more advanced topics
+            like plugins, theming, and deployment to production.</p>
+            <p>Once installed, run the init command to scaffold a new project.
+            The generated files include a configuration template, a sample
+            component, and a README with further setup instructions for your
+            specific environment and toolchain preferences.</p>
+        </article></body></html>";
+
+    #[test]
+    fn test_flags_spa_shell_with_explicit_js_marker() {
+        let markdown = "You need to enable JavaScript to run this app.";
+        assert_eq!(
+            detect_spa_shell(SPA_SHELL_HTML, markdown),
+            Some(SPA_SHELL_WARNING)
+        );
+    }
+
+    #[test]
+    fn test_flags_short_page_with_high_script_ratio_even_without_marker() {
+        let html = r#"<html><body>
+            <script>const a = 1;</script>
+            <script>const b = 2; const c = 3; const d = 4; const e = 5;</script>
+            <div id="app"></div>
+        </body></html>"#;
+        let markdown = "Loading";
+        assert_eq!(detect_spa_shell(html, markdown), Some(SPA_SHELL_WARNING));
+    }
+
+    #[test]
+    fn test_does_not_flag_real_article_content() {
+        let markdown = "Getting Started This guide walks through installing the toolkit, \
+            configuring your first project, and running the development server. It \
+            covers the core concepts you need before moving on to more advanced topics \
+            like plugins, theming, and deployment to production. Once installed, run \
+            the init command to scaffold a new project. The generated files include a \
+            configuration template, a sample component, and a README with further \
+            setup instructions for your specific environment and toolchain preferences.";
+        assert_eq!(detect_spa_shell(REAL_CONTENT_HTML, markdown), None);
+    }
+
+    #[test]
+    fn test_does_not_flag_genuinely_short_page_without_js_signals() {
+        let html = "<html><body><p>See the full docs at example.com/docs.</p></body></html>";
+        let markdown = "See the full docs at example.com/docs.";
+        assert_eq!(detect_spa_shell(html, markdown), None);
+    }
+
+    #[test]
+    fn test_script_ratio_ignores_empty_html() {
+        assert!(script_ratio("").abs() < f64::EPSILON);
+    }
+
+    const DOCS_LIKE_MARKDOWN: &str = "# Getting Started\n\n\
+        This guide walks through installing the toolkit, configuring your \
+        first project, and running the development server against a local \
+        checkout before deploying anywhere else.\n\n\
+        ## Installation\n\n\
+        Install the package from your package manager of choice, then verify \
+        the install by printing its version from a terminal.\n\n\
+        ```sh\n\
+        npm install toolkit\n\
+        toolkit --version\n\
+        ```\n\n\
+        ## Configuration\n\n\
+        Create a configuration file in the project root. Most options have \
+        sensible defaults, so a minimal file is enough to get started before \
+        tuning anything further.";
+
+    const MARKETING_PAGE_MARKDOWN: &str = "Build faster.\n\n\
+        Ship with confidence.\n\n\
+        The platform trusted by teams everywhere.\n\n\
+        Get Started\n\n\
+        Pricing\n\n\
+        Docs\n\n\
+        Blog\n\n\
+        Contact Sales\n\n\
+        Loved by developers.\n\n\
+        Trusted by the world's best teams.\n\n\
+        Start your free trial today.\n\n\
+        No credit card required.\n\n\
+        Cancel anytime.\n\n\
+        See why thousands of teams switched.\n\n\
+        Join the waitlist.\n\n\
+        Talk to sales.\n\n\
+        Read the case studies.\n\n\
+        Explore our customers.\n\n\
+        Request a demo.";
+
+    #[test]
+    fn test_does_not_flag_docs_like_content() {
+        assert!(!detect_not_docs(DOCS_LIKE_MARKDOWN));
+    }
+
+    #[test]
+    fn test_flags_marketing_page_content() {
+        assert!(detect_not_docs(MARKETING_PAGE_MARKDOWN));
+    }
+
+    #[test]
+    fn test_does_not_flag_short_content_regardless_of_shape() {
+        assert!(!detect_not_docs("Get Started\n\nPricing\n\nDocs"));
+    }
+}
@@ -0,0 +1,384 @@
+//! Pluggable HTML→Markdown conversion, selected per fetch by name.
+//!
+//! `FetchServer` (in the `llms-fetch-mcp` binary) uses a `FetchPipeline`
+//! internally, built from the two converters registered here by default.
+//! Library users can register their own `ContentConverter` implementations
+//! (e.g. a converter backed by `htmd`, or one that calls out to a headless
+//! browser service) via `FetchPipeline::builder().register(...)` without
+//! forking the crate.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use dom_smoothie::{Config, Readability, TextMode};
+
+use crate::admonitions;
+use crate::sanitize;
+use crate::tables;
+
+/// Everything a `ContentConverter` needs to resolve relative links and
+/// decide how to handle the body: the final URL after redirects, the raw
+/// `Content-Type` header, the parsed charset (if present), and the body
+/// itself. `preserve_tables` carries the per-call table-handling preference
+/// through, since it's a property of this specific conversion rather than
+/// of the converter implementation.
+pub struct RawContent {
+    pub url: String,
+    /// Consulted by converters that need to branch on MIME type
+    #[allow(dead_code)]
+    pub content_type: String,
+    /// Consulted by converters that need to decode non-UTF-8 bodies
+    #[allow(dead_code)]
+    pub charset: Option<String>,
+    pub body: String,
+    pub preserve_tables: bool,
+    /// Removal selectors consulted by converters that strip chrome before
+    /// extraction (currently only `ReadabilityConverter`), resolved from
+    /// `FetchInput.html_sanitize_level` plus any `--site-config`
+    /// `CleanConfig` overrides for this host (see `sanitize::CleanConfig::resolve`)
+    pub remove_selectors: Vec<String>,
+    /// When true, convert admonition/callout boxes (see `admonition_classes`)
+    /// to labeled markdown blockquotes instead of letting them flatten into
+    /// plain paragraphs
+    pub keep_admonitions: bool,
+    /// Admonition class name -> blockquote label mapping consulted when
+    /// `keep_admonitions` is set, e.g. `("warning", "Warning")`
+    pub admonition_classes: Vec<(String, String)>,
+}
+
+/// Parses the `charset` parameter out of a `Content-Type` header value,
+/// e.g. `"text/html; charset=utf-8"` -> `Some("utf-8")`.
+pub fn parse_charset(content_type: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        (key.trim().eq_ignore_ascii_case("charset")).then(|| value.trim().to_string())
+    })
+}
+
+/// The markdown produced by a `ContentConverter`.
+#[derive(Debug)]
+pub struct ConvertedContent {
+    pub markdown: String,
+}
+
+/// Converts a fetched HTML response into markdown. Implementations are
+/// registered on a `FetchPipeline` and selected per fetch by name.
+pub trait ContentConverter: Send + Sync {
+    fn convert(&self, raw: &RawContent) -> Result<ConvertedContent, String>;
+}
+
+/// The default converter: cleans the HTML with `dom_smoothie`'s Readability
+/// (stripping navigation, sidebars, and other chrome that isn't part of the
+/// article body) before converting to markdown via `html2md`.
+pub struct ReadabilityConverter;
+
+impl ContentConverter for ReadabilityConverter {
+    fn convert(&self, raw: &RawContent) -> Result<ConvertedContent, String> {
+        if raw.body.trim().is_empty() {
+            return Err("HTML content is empty".to_string());
+        }
+
+        let sanitized_body =
+            sanitize::strip_chrome_with_selectors(&raw.body, &raw.remove_selectors);
+
+        let cfg = Config {
+            text_mode: TextMode::Raw,
+            ..Default::default()
+        };
+        let mut readability =
+            Readability::new(sanitized_body.as_str(), Some(raw.url.as_str()), Some(cfg))
+                .map_err(|e| e.to_string())?;
+        let article = readability.parse().map_err(|e| e.to_string())?;
+
+        let cleaned_html = article.content.to_string();
+        let cleaned_html = if raw.preserve_tables {
+            tables::preprocess_tables(&cleaned_html)
+        } else {
+            cleaned_html
+        };
+        let cleaned_html = if raw.keep_admonitions {
+            admonitions::convert_admonitions(&cleaned_html, &raw.admonition_classes)
+        } else {
+            cleaned_html
+        };
+        let markdown = html2md::parse_html(&cleaned_html);
+
+        if markdown.trim().is_empty() {
+            return Err(
+                "Extracted content is empty (page may have no readable content)".to_string(),
+            );
+        }
+
+        Ok(ConvertedContent { markdown })
+    }
+}
+
+/// Converts HTML to markdown directly via `html2md`, skipping Readability's
+/// chrome-stripping pass. Useful for pages where Readability misidentifies
+/// the article body, or strips content that's wanted as-is.
+pub struct RawHtmlConverter;
+
+impl ContentConverter for RawHtmlConverter {
+    fn convert(&self, raw: &RawContent) -> Result<ConvertedContent, String> {
+        if raw.body.trim().is_empty() {
+            return Err("HTML content is empty".to_string());
+        }
+
+        let html = if raw.preserve_tables {
+            tables::preprocess_tables(&raw.body)
+        } else {
+            raw.body.clone()
+        };
+        let html = if raw.keep_admonitions {
+            admonitions::convert_admonitions(&html, &raw.admonition_classes)
+        } else {
+            html
+        };
+        let markdown = html2md::parse_html(&html);
+
+        if markdown.trim().is_empty() {
+            return Err(
+                "Extracted content is empty (page may have no readable content)".to_string(),
+            );
+        }
+
+        Ok(ConvertedContent { markdown })
+    }
+}
+
+pub const READABILITY: &str = "readability";
+pub const RAW_HTML: &str = "raw-html";
+
+/// A named set of `ContentConverter`s with a default, used by `FetchServer`
+/// to resolve the per-call/site-config/server-default converter selection
+/// down to a single implementation.
+pub struct FetchPipeline {
+    converters: HashMap<String, Arc<dyn ContentConverter>>,
+    default: String,
+}
+
+impl FetchPipeline {
+    pub fn builder() -> FetchPipelineBuilder {
+        FetchPipelineBuilder::default()
+    }
+
+    /// Converts `raw` with the converter named `name`, or the pipeline's
+    /// default converter if `name` is `None`. Errors if `name` doesn't
+    /// match any registered converter.
+    pub fn convert(
+        &self,
+        name: Option<&str>,
+        raw: &RawContent,
+    ) -> Result<ConvertedContent, String> {
+        let key = name.unwrap_or(&self.default);
+        let converter = self
+            .converters
+            .get(key)
+            .ok_or_else(|| format!("unknown converter '{key}'"))?;
+        converter.convert(raw)
+    }
+}
+
+/// Builds a `FetchPipeline`, pre-registered with the `readability` and
+/// `raw-html` built-ins (`readability` is the default unless overridden).
+pub struct FetchPipelineBuilder {
+    converters: HashMap<String, Arc<dyn ContentConverter>>,
+    default: String,
+}
+
+impl Default for FetchPipelineBuilder {
+    fn default() -> Self {
+        let mut converters: HashMap<String, Arc<dyn ContentConverter>> = HashMap::new();
+        converters.insert(READABILITY.to_string(), Arc::new(ReadabilityConverter));
+        converters.insert(RAW_HTML.to_string(), Arc::new(RawHtmlConverter));
+        Self {
+            converters,
+            default: READABILITY.to_string(),
+        }
+    }
+}
+
+impl FetchPipelineBuilder {
+    /// Registers `converter` under `name`, overwriting any existing
+    /// converter of that name (including the built-ins). Unused within the
+    /// bin, which only registers the two built-in converters; exists for
+    /// library consumers building their own `FetchPipeline`.
+    #[must_use]
+    #[allow(dead_code)]
+    pub fn register(
+        mut self,
+        name: impl Into<String>,
+        converter: Arc<dyn ContentConverter>,
+    ) -> Self {
+        self.converters.insert(name.into(), converter);
+        self
+    }
+
+    /// Sets which registered converter `FetchPipeline::convert` falls back
+    /// to when called with `name: None`. Must be registered (built-in or
+    /// custom) by the time `build` is called.
+    #[must_use]
+    pub fn default_converter(mut self, name: impl Into<String>) -> Self {
+        self.default = name.into();
+        self
+    }
+
+    pub fn build(self) -> FetchPipeline {
+        FetchPipeline {
+            converters: self.converters,
+            default: self.default,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHROME_HEAVY_HTML: &str = r"
+        <html><body>
+            <nav>Site nav: Home | Docs | Blog</nav>
+            <article><p>The actual article content, long enough to survive Readability's extraction.</p></article>
+            <footer>Copyright 2024</footer>
+        </body></html>
+    ";
+
+    fn raw(body: &str) -> RawContent {
+        RawContent {
+            url: "https://example.com/page".to_string(),
+            content_type: "text/html; charset=utf-8".to_string(),
+            charset: Some("utf-8".to_string()),
+            body: body.to_string(),
+            preserve_tables: false,
+            remove_selectors: sanitize::CleanConfig::default().resolve(
+                sanitize::SanitizeLevel::Standard,
+                None,
+                None,
+            ),
+            keep_admonitions: false,
+            admonition_classes: admonitions::default_admonition_classes(),
+        }
+    }
+
+    #[test]
+    fn test_parse_charset_extracts_value() {
+        assert_eq!(
+            parse_charset("text/html; charset=utf-8"),
+            Some("utf-8".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_charset_absent_is_none() {
+        assert_eq!(parse_charset("text/html"), None);
+    }
+
+    #[test]
+    fn test_readability_and_raw_html_converters_produce_different_output() {
+        let readability = ReadabilityConverter
+            .convert(&raw(CHROME_HEAVY_HTML))
+            .unwrap();
+        let raw_html = RawHtmlConverter.convert(&raw(CHROME_HEAVY_HTML)).unwrap();
+
+        assert!(readability.markdown.contains("actual article content"));
+        assert!(!readability.markdown.contains("Site nav"));
+        assert!(raw_html.markdown.contains("Site nav"));
+        assert_ne!(readability.markdown, raw_html.markdown);
+    }
+
+    #[test]
+    fn test_raw_html_converter_keeps_admonitions_as_labeled_blockquotes() {
+        let html = r#"<html><body><article><p>Intro text.</p>
+            <div class="warning"><p>This API is deprecated and will be removed.</p></div>
+            <p>More article content, long enough to not be trimmed away.</p>
+            </article></body></html>"#;
+        let mut input = raw(html);
+        input.keep_admonitions = true;
+
+        let result = RawHtmlConverter.convert(&input).unwrap();
+        assert!(
+            result
+                .markdown
+                .contains("> **Warning:** This API is deprecated and will be removed."),
+            "{}",
+            result.markdown
+        );
+    }
+
+    #[test]
+    fn test_admonitions_left_as_divs_when_keep_admonitions_is_false() {
+        let html = r#"<div class="warning"><p>Deprecated.</p></div>"#;
+        let result = RawHtmlConverter.convert(&raw(html)).unwrap();
+        assert!(!result.markdown.contains("**Warning:**"));
+    }
+
+    #[test]
+    fn test_pipeline_dispatches_by_name() {
+        let pipeline = FetchPipeline::builder().build();
+        let via_readability = pipeline
+            .convert(Some(READABILITY), &raw(CHROME_HEAVY_HTML))
+            .unwrap();
+        let via_raw_html = pipeline
+            .convert(Some(RAW_HTML), &raw(CHROME_HEAVY_HTML))
+            .unwrap();
+        assert_ne!(via_readability.markdown, via_raw_html.markdown);
+    }
+
+    #[test]
+    fn test_pipeline_falls_back_to_default_when_name_is_none() {
+        let pipeline = FetchPipeline::builder().default_converter(RAW_HTML).build();
+        let result = pipeline.convert(None, &raw(CHROME_HEAVY_HTML)).unwrap();
+        assert!(result.markdown.contains("Site nav"));
+    }
+
+    #[test]
+    fn test_pipeline_errors_on_unknown_converter_name() {
+        let pipeline = FetchPipeline::builder().build();
+        let err = pipeline
+            .convert(Some("nonexistent"), &raw("<p>x</p>"))
+            .unwrap_err();
+        assert!(err.contains("nonexistent"));
+    }
+
+    struct UppercaseConverter;
+    impl ContentConverter for UppercaseConverter {
+        fn convert(&self, raw: &RawContent) -> Result<ConvertedContent, String> {
+            Ok(ConvertedContent {
+                markdown: raw.body.to_uppercase(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_custom_registered_converter_is_used_when_selected() {
+        let pipeline = FetchPipeline::builder()
+            .register("shout", Arc::new(UppercaseConverter))
+            .build();
+        let result = pipeline.convert(Some("shout"), &raw("hello")).unwrap();
+        assert_eq!(result.markdown, "HELLO");
+    }
+
+    const DOCS_PAGE_HTML: &str = r"<html><body>
+            <nav>Home | Guide | API Reference</nav>
+            <article>
+                <h1>Getting Started</h1>
+                <p>This guide walks through installing the toolkit, configuring
+                your first project, and running the development server.</p>
+            </article>
+            <footer>Copyright 2026</footer>
+        </body></html>";
+
+    /// `FetchInput.converter` is the extractor switch for a page Readability
+    /// mangles: this pins both extractors' output on a realistic docs page
+    /// so an agent retrying with the other value gets a visibly different
+    /// result, not the same markdown twice.
+    #[test]
+    fn test_readability_and_raw_html_extractors_differ_on_docs_fixture() {
+        let readability = ReadabilityConverter.convert(&raw(DOCS_PAGE_HTML)).unwrap();
+        let raw_html = RawHtmlConverter.convert(&raw(DOCS_PAGE_HTML)).unwrap();
+
+        assert!(readability.markdown.contains("Getting Started"));
+        assert!(!readability.markdown.contains("Home | Guide"));
+        assert!(raw_html.markdown.contains("Home | Guide"));
+    }
+}
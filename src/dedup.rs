@@ -0,0 +1,183 @@
+//! Near-duplicate content detection for `FetchInput.deduplicate_content`.
+//!
+//! Documentation sites often serve the same page under several paths
+//! (`/stable`, `/latest`, `/v3.x`). Rather than a full diff, each fetched
+//! document is reduced to a 64-bit `SimHash` fingerprint over a sample of its
+//! 3-word shingles, which can be compared against previously cached
+//! fingerprints in near-constant time.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+const SHINGLE_WORDS: usize = 3;
+/// Shingles are sampled rather than exhaustively hashed, per the request's
+/// "64 shingles of 3-word grams is sufficient" sizing.
+const MAX_SHINGLES: usize = 64;
+const HASH_BITS: u32 = 64;
+/// Similarity above which two documents are treated as duplicates.
+const DUPLICATE_THRESHOLD: f64 = 0.95;
+
+/// Hashes `text`'s 3-word shingles into a 64-bit `SimHash` fingerprint: each
+/// sampled shingle is hashed, and every bit position votes +1/-1 across all
+/// sampled hashes, with the final fingerprint bit set wherever the vote is
+/// positive. Shingles are sampled at an even stride rather than exhaustively,
+/// so a 2MB llms-full.txt costs at most `MAX_SHINGLES` hashes rather than one
+/// per word.
+pub fn simhash(text: &str) -> u64 {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.len() < SHINGLE_WORDS {
+        return hash_shingle(text);
+    }
+
+    let total_shingles = words.len() - SHINGLE_WORDS + 1;
+    let stride = (total_shingles / MAX_SHINGLES).max(1);
+
+    let mut votes = [0i32; HASH_BITS as usize];
+    let mut sampled = 0;
+    let mut start = 0;
+    while start < total_shingles && sampled < MAX_SHINGLES {
+        let shingle_hash = hash_shingle(&words[start..start + SHINGLE_WORDS].join(" "));
+        for (bit, vote) in votes.iter_mut().enumerate() {
+            if shingle_hash & (1 << bit) == 0 {
+                *vote -= 1;
+            } else {
+                *vote += 1;
+            }
+        }
+        sampled += 1;
+        start += stride;
+    }
+
+    votes.iter().enumerate().fold(
+        0u64,
+        |acc, (bit, &vote)| if vote > 0 { acc | (1 << bit) } else { acc },
+    )
+}
+
+fn hash_shingle(shingle: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    shingle.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Fraction of matching bits between two `SimHash` fingerprints (1.0 = identical, 0.0 = opposite).
+pub fn similarity(a: u64, b: u64) -> f64 {
+    1.0 - f64::from((a ^ b).count_ones()) / f64::from(HASH_BITS)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HashEntry {
+    path: String,
+    simhash: u64,
+}
+
+/// Persisted record of every fingerprint written so far for one cache
+/// directory, stored as `.hashes.json` at its root.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HashStore {
+    entries: Vec<HashEntry>,
+}
+
+impl HashStore {
+    fn store_path(cache_dir: &Path) -> PathBuf {
+        cache_dir.join(".hashes.json")
+    }
+
+    /// Loads the store from `cache_dir`, or an empty one if it doesn't exist
+    /// yet or is unreadable.
+    pub async fn load(cache_dir: &Path) -> Self {
+        let Ok(bytes) = fs::read(Self::store_path(cache_dir)).await else {
+            return Self::default();
+        };
+        serde_json::from_slice(&bytes).unwrap_or_default()
+    }
+
+    /// Returns the cache path of the most similar existing entry whose
+    /// similarity to `hash` exceeds `DUPLICATE_THRESHOLD`, if any.
+    pub fn find_duplicate(&self, hash: u64) -> Option<&str> {
+        self.entries
+            .iter()
+            .map(|entry| (entry.path.as_str(), similarity(entry.simhash, hash)))
+            .filter(|(_, sim)| *sim > DUPLICATE_THRESHOLD)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(path, _)| path)
+    }
+
+    pub fn insert(&mut self, path: String, hash: u64) {
+        self.entries.push(HashEntry {
+            path,
+            simhash: hash,
+        });
+    }
+
+    /// Writes the store back to `.hashes.json` via temp-file + rename, to
+    /// match the atomic-write convention used for cache files themselves.
+    pub async fn save(&self, cache_dir: &Path) -> std::io::Result<()> {
+        let contents = serde_json::to_vec_pretty(self)
+            .map_err(|e| std::io::Error::other(format!("serializing dedup hash store: {e}")))?;
+        let final_path = Self::store_path(cache_dir);
+        let temp_path = final_path.with_extension("json.tmp");
+        fs::write(&temp_path, contents).await?;
+        fs::rename(&temp_path, &final_path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_text_has_identical_hash() {
+        let text = "The quick brown fox jumps over the lazy dog repeatedly and often";
+        assert_eq!(simhash(text), simhash(text));
+    }
+
+    #[test]
+    fn test_near_identical_text_is_highly_similar() {
+        let a = "Getting started with the widget toolkit requires installing the core \
+                 package and configuring your first widget before anything else works";
+        let b = "Getting started with the widget toolkit requires installing the core \
+                 package and configuring your first widget before anything else works!";
+        let sim = similarity(simhash(a), simhash(b));
+        assert!(sim > 0.95, "expected high similarity, got {sim}");
+    }
+
+    #[test]
+    fn test_unrelated_text_is_not_similar() {
+        let a = "Getting started with the widget toolkit requires installing the core package";
+        let b = "Our refund policy covers purchases made within the last thirty calendar days";
+        let sim = similarity(simhash(a), simhash(b));
+        assert!(sim < 0.95, "expected low similarity, got {sim}");
+    }
+
+    #[test]
+    fn test_short_text_falls_back_to_whole_string_hash() {
+        assert_eq!(simhash("hi there"), simhash("hi there"));
+        assert_ne!(simhash("hi there"), simhash("bye now"));
+    }
+
+    #[tokio::test]
+    async fn test_hash_store_round_trips_and_finds_duplicate() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut store = HashStore::load(temp_dir.path()).await;
+        assert!(store.entries.is_empty());
+
+        let hash = simhash("Getting started with the widget toolkit requires installing it");
+        store.insert("example.com/stable/index.md".to_string(), hash);
+        store.save(temp_dir.path()).await.unwrap();
+
+        let reloaded = HashStore::load(temp_dir.path()).await;
+        assert_eq!(
+            reloaded.find_duplicate(hash),
+            Some("example.com/stable/index.md")
+        );
+        assert_eq!(
+            reloaded.find_duplicate(simhash("totally unrelated content here")),
+            None
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Extracts a short summary for `FileInfo.description`, used to build a
+//! searchable index of cached docs without having to open every file.
+
+use scraper::{Html, Selector};
+
+/// Descriptions longer than this are truncated (on a char boundary) so a
+/// single page can't blow out the size of an index built from many of
+/// these.
+pub const MAX_DESCRIPTION_LEN: usize = 300;
+
+/// Prefers `<meta name="description" content="...">`, then the `description`
+/// field of embedded JSON-LD, then the first non-empty `<p>`. HTML entities
+/// are already decoded by the underlying parser. Returns `None` if nothing
+/// usable is found. The result is truncated to `MAX_DESCRIPTION_LEN` bytes
+/// (on a char boundary).
+pub fn extract_description(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+
+    let meta_selector = Selector::parse(r#"meta[name="description"]"#).unwrap();
+    let meta_description = document
+        .select(&meta_selector)
+        .find_map(|meta| meta.value().attr("content"))
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    let description = meta_description.map(ToString::to_string).or_else(|| {
+        crate::json_ld::extract_json_ld(html)
+            .get("description")
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    });
+
+    let description = description.or_else(|| {
+        let p_selector = Selector::parse("p").unwrap();
+        document.select(&p_selector).find_map(|p| {
+            let text = p.text().collect::<String>();
+            let text = text.trim();
+            (!text.is_empty()).then(|| text.to_string())
+        })
+    })?;
+
+    Some(truncate_at_char_boundary(&description, MAX_DESCRIPTION_LEN))
+}
+
+fn truncate_at_char_boundary(s: &str, max_len: usize) -> String {
+    if s.len() <= max_len {
+        return s.to_string();
+    }
+    let mut end = max_len;
+    while end > 0 && !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    s[..end].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_meta_description() {
+        let html = r#"<html><head>
+            <meta name="description" content="A guide to installing the toolkit.">
+            </head><body><p>Something else entirely.</p></body></html>"#;
+        assert_eq!(
+            extract_description(html),
+            Some("A guide to installing the toolkit.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_falls_back_to_first_paragraph() {
+        let html = r"<html><body>
+            <p>This is the first paragraph of the page.</p>
+            <p>This is a second paragraph.</p>
+            </body></html>";
+        assert_eq!(
+            extract_description(html),
+            Some("This is the first paragraph of the page.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_falls_back_to_json_ld_description() {
+        let html = r#"<html><head>
+            <script type="application/ld+json">
+            {"@type": "Article", "description": "Described via structured data."}
+            </script>
+            </head><body><p>Unrelated paragraph text.</p></body></html>"#;
+        assert_eq!(
+            extract_description(html),
+            Some("Described via structured data.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decodes_html_entities() {
+        let html = r#"<meta name="description" content="Cats &amp; dogs &mdash; a guide">"#;
+        assert_eq!(
+            extract_description(html),
+            Some("Cats & dogs — a guide".to_string())
+        );
+    }
+
+    #[test]
+    fn test_no_description_or_paragraph_returns_none() {
+        let html = "<html><body><h1>Just a heading</h1></body></html>";
+        assert_eq!(extract_description(html), None);
+    }
+
+    #[test]
+    fn test_truncates_long_description() {
+        let long = "x".repeat(500);
+        let html = format!(r#"<meta name="description" content="{long}">"#);
+        let result = extract_description(&html).unwrap();
+        assert_eq!(result.len(), MAX_DESCRIPTION_LEN);
+    }
+}
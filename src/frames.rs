@@ -0,0 +1,117 @@
+//! Detection of frameset/iframe content targets for recovering documentation
+//! built on `<frame>`/`<iframe>` navigation (old Javadoc, Doxygen), where the
+//! Readability pass otherwise discards the frame elements and leaves the
+//! page looking empty. See `FetchServer::try_frame_recovery`.
+
+use scraper::{Html, Selector};
+use url::Url;
+
+/// Substrings of a frame/iframe's `name`/`id` that suggest it holds the main
+/// document body rather than a navigation or table-of-contents sidebar.
+const CONTENT_HINTS: &[&str] = &["main", "content", "body", "classFrame", "packageFrame"];
+
+/// True if `html` defines a `<frameset>` rather than a `<body>`. Readability
+/// extraction (`dom_smoothie`) assumes a `<body>` is present and panics on a
+/// pure frameset document, so callers must skip the normal conversion
+/// pipeline for these and go straight to `find_frame_target` instead.
+pub fn looks_like_frameset(html: &str) -> bool {
+    Html::parse_document(html)
+        .select(&Selector::parse("frameset").unwrap())
+        .next()
+        .is_some()
+}
+
+/// Finds the single best `<frame src>` or `<iframe src>` to recover content
+/// from, resolving each `src` against `base_url` and keeping only same-host
+/// targets (cross-host frames are usually ads or embeds, not primary
+/// content). Among candidates, prefers one whose `name`/`id` matches
+/// `CONTENT_HINTS`, then the one with the most attribute text (a rough proxy
+/// for "the frame the author bothered to describe"), then document order.
+/// Returns `None` if `html` has no frames, or none resolve to the same host.
+pub fn find_frame_target(html: &str, base_url: &str) -> Option<String> {
+    let Ok(base) = Url::parse(base_url) else {
+        return None;
+    };
+    let selector = Selector::parse("frame[src], iframe[src]").unwrap();
+    let document = Html::parse_document(html);
+
+    document
+        .select(&selector)
+        .filter_map(|frame| {
+            let src = frame.value().attr("src")?;
+            let target = base.join(src).ok()?;
+            if target.host_str() != base.host_str() {
+                return None;
+            }
+            let label = frame_label(&frame).to_lowercase();
+            let hint_score = i32::from(
+                CONTENT_HINTS
+                    .iter()
+                    .any(|hint| label.contains(&hint.to_lowercase())),
+            );
+            Some((hint_score, frame_label(&frame).len(), target.to_string()))
+        })
+        .max_by_key(|&(hint_score, label_len, _)| (hint_score, label_len))
+        .map(|(_, _, url)| url)
+}
+
+fn frame_label(frame: &scraper::ElementRef) -> String {
+    let value = frame.value();
+    format!(
+        "{}{}",
+        value.attr("name").unwrap_or_default(),
+        value.attr("id").unwrap_or_default()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_looks_like_frameset_detects_frameset_doc() {
+        let html = r#"<html><frameset><frame src="a.html"></frameset></html>"#;
+        assert!(looks_like_frameset(html));
+    }
+
+    #[test]
+    fn test_looks_like_frameset_false_for_ordinary_body() {
+        assert!(!looks_like_frameset(
+            "<html><body><iframe src=\"a.html\"></iframe></body></html>"
+        ));
+    }
+
+    #[test]
+    fn test_picks_frame_with_content_hint() {
+        let html = r#"
+            <html><frameset cols="20%,80%">
+            <frame src="nav.html" name="navFrame">
+            <frame src="main.html" name="classFrame">
+            </frameset></html>
+        "#;
+        let target = find_frame_target(html, "https://docs.example.com/index.html").unwrap();
+        assert_eq!(target, "https://docs.example.com/main.html");
+    }
+
+    #[test]
+    fn test_ignores_cross_host_frame() {
+        let html = r#"<iframe src="https://ads.example.net/banner.html" name="ad"></iframe>"#;
+        assert!(find_frame_target(html, "https://docs.example.com/index.html").is_none());
+    }
+
+    #[test]
+    fn test_no_frames_returns_none() {
+        assert!(
+            find_frame_target("<html><body>No frames here</body></html>", "https://x.com/")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_relative_src_resolved_against_base() {
+        let html =
+            r#"<html><frameset><frame src="sub/dir/page.html" name="content"></frameset></html>"#;
+        let target = find_frame_target(html, "https://docs.example.com/a/index.html").unwrap();
+        assert_eq!(target, "https://docs.example.com/a/sub/dir/page.html");
+    }
+}
@@ -0,0 +1,339 @@
+//! Directory-listing fallback for GitHub `/tree/` URLs with no README (common
+//! for `src/` subtrees), used when every raw-content variation the fetch
+//! tool tries for that URL comes back 404.
+//!
+//! The contents API is tried first (authenticated with the caller-supplied
+//! token if one was resolved from `FetchInput.github_token`/`GITHUB_TOKEN`,
+//! otherwise relying on GitHub's unauthenticated rate limit); if that call
+//! fails, the file list is scraped out of the tree page's embedded JSON
+//! payload instead.
+
+use std::fmt::Write as _;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Deserialize;
+
+/// The owner/repo/branch/path parsed out of a `github.com/.../tree/...` URL.
+pub struct TreeRef {
+    pub owner: String,
+    pub repo: String,
+    pub branch: String,
+    pub path: String,
+}
+
+/// Parses a `github.com/{owner}/{repo}/tree/{branch}/{path...}` URL. Returns
+/// `None` for anything else (including `/blob/` URLs, which point at a file
+/// rather than a directory). Branch names containing `/` are ambiguous from
+/// the URL alone, so — matching the simplest split `github_raw_variations`
+/// tries — the first path segment after `tree` is assumed to be the whole
+/// branch name.
+pub fn parse_tree_url(url: &str) -> Option<TreeRef> {
+    let parsed = url::Url::parse(url).ok()?;
+    if parsed.host_str() != Some("github.com") {
+        return None;
+    }
+
+    let segments: Vec<&str> = parsed.path().trim_matches('/').split('/').collect();
+    if segments.len() < 3 || segments[2] != "tree" {
+        return None;
+    }
+
+    Some(TreeRef {
+        owner: segments[0].to_string(),
+        repo: segments[1].to_string(),
+        branch: segments.get(3).copied().unwrap_or_default().to_string(),
+        path: segments[4..].join("/"),
+    })
+}
+
+const CONTENTS_API: &str = "https://api.github.com";
+
+#[derive(Debug, Deserialize)]
+struct ContentsEntry {
+    name: String,
+    #[serde(rename = "type")]
+    entry_type: String,
+    size: u64,
+    html_url: Option<String>,
+}
+
+/// Calls the contents API for `tree`'s path and renders the entries as a
+/// markdown table. Returns `None` on any network/parse error, a non-2xx
+/// response (e.g. rate limited, or the path doesn't exist), or if the path
+/// turns out to be a file rather than a directory.
+pub async fn fetch_listing(
+    client: &reqwest::Client,
+    tree: &TreeRef,
+    token: Option<&str>,
+) -> Option<String> {
+    fetch_listing_at(client, CONTENTS_API, tree, token).await
+}
+
+/// Same as `fetch_listing`, but against a caller-supplied API base; split
+/// out so tests can point it at a mock server.
+async fn fetch_listing_at(
+    client: &reqwest::Client,
+    api_base: &str,
+    tree: &TreeRef,
+    token: Option<&str>,
+) -> Option<String> {
+    let url = format!(
+        "{api_base}/repos/{}/{}/contents/{}",
+        tree.owner, tree.repo, tree.path
+    );
+
+    let mut request = client
+        .get(&url)
+        .query(&[("ref", &tree.branch)])
+        .header("Accept", "application/vnd.github+json")
+        .header(
+            "User-Agent",
+            "llms-fetch-mcp/0.1.3 (+https://github.com/crazytieguy/llms-fetch-mcp)",
+        );
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request.send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+
+    let entries: Vec<ContentsEntry> = response.json().await.ok()?;
+    Some(render_listing(tree, &entries))
+}
+
+fn render_listing(tree: &TreeRef, entries: &[ContentsEntry]) -> String {
+    let mut sorted: Vec<&ContentsEntry> = entries.iter().collect();
+    sorted.sort_by(|a, b| (a.entry_type != "dir", &a.name).cmp(&(b.entry_type != "dir", &b.name)));
+
+    let mut out = format!("# {}/{}/{}\n\n", tree.owner, tree.repo, tree.path);
+    out.push_str("| Name | Type | Size | Link |\n|---|---|---|---|\n");
+    for entry in sorted {
+        let kind = if entry.entry_type == "dir" {
+            "directory"
+        } else {
+            "file"
+        };
+        let link = entry.html_url.as_deref().unwrap_or("");
+        let _ = writeln!(out, "| {} | {kind} | {} | {link} |", entry.name, entry.size);
+    }
+    out
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddedPayload {
+    payload: Payload,
+}
+
+#[derive(Debug, Deserialize)]
+struct Payload {
+    tree: Tree,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tree {
+    items: Vec<TreeItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TreeItem {
+    name: String,
+    path: String,
+    #[serde(rename = "contentType")]
+    content_type: String,
+}
+
+static EMBEDDED_DATA: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<script type="application/json" data-target="react-app\.embeddedData">(.*?)</script>"#)
+        .unwrap()
+});
+
+/// Scrapes the file list out of a GitHub tree page's embedded
+/// `react-app.embeddedData` JSON payload. Returns `None` if the payload
+/// isn't present or doesn't parse as expected.
+fn scrape_listing_from_html(html: &str, tree: &TreeRef) -> Option<String> {
+    let json = EMBEDDED_DATA.captures(html)?.get(1)?.as_str();
+    let parsed: EmbeddedPayload = serde_json::from_str(json).ok()?;
+    if parsed.payload.tree.items.is_empty() {
+        return None;
+    }
+    Some(render_scraped_listing(tree, &parsed.payload.tree.items))
+}
+
+fn render_scraped_listing(tree: &TreeRef, items: &[TreeItem]) -> String {
+    let mut sorted: Vec<&TreeItem> = items.iter().collect();
+    sorted.sort_by(|a, b| {
+        (a.content_type != "directory", &a.name).cmp(&(b.content_type != "directory", &b.name))
+    });
+
+    let mut out = format!("# {}/{}/{}\n\n", tree.owner, tree.repo, tree.path);
+    out.push_str("| Name | Type | Link |\n|---|---|---|\n");
+    for item in sorted {
+        let kind = if item.content_type == "directory" {
+            "directory"
+        } else {
+            "file"
+        };
+        let verb = if item.content_type == "directory" {
+            "tree"
+        } else {
+            "blob"
+        };
+        let url = format!(
+            "https://github.com/{}/{}/{verb}/{}/{}",
+            tree.owner, tree.repo, tree.branch, item.path
+        );
+        let _ = writeln!(out, "| {} | {kind} | {url} |", item.name);
+    }
+    out
+}
+
+/// Produces a markdown directory listing for `tree`: the contents API if it
+/// succeeds, otherwise the file list scraped out of `tree_url`'s HTML.
+pub async fn directory_listing(
+    client: &reqwest::Client,
+    tree_url: &str,
+    tree: &TreeRef,
+    token: Option<&str>,
+) -> Option<String> {
+    if let Some(listing) = fetch_listing(client, tree, token).await {
+        return Some(listing);
+    }
+
+    let response = client
+        .get(tree_url)
+        .header(
+            "User-Agent",
+            "llms-fetch-mcp/0.1.3 (+https://github.com/crazytieguy/llms-fetch-mcp)",
+        )
+        .send()
+        .await
+        .ok()?;
+    let html = response.text().await.ok()?;
+    scrape_listing_from_html(&html, tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tree_url_extracts_owner_repo_branch_path() {
+        let tree = parse_tree_url("https://github.com/rust-lang/rust/tree/main/src/tools").unwrap();
+        assert_eq!(tree.owner, "rust-lang");
+        assert_eq!(tree.repo, "rust");
+        assert_eq!(tree.branch, "main");
+        assert_eq!(tree.path, "src/tools");
+    }
+
+    #[test]
+    fn test_parse_tree_url_rejects_blob_urls() {
+        assert!(parse_tree_url("https://github.com/rust-lang/rust/blob/main/README.md").is_none());
+    }
+
+    #[test]
+    fn test_parse_tree_url_rejects_non_github_hosts() {
+        assert!(parse_tree_url("https://example.com/rust-lang/rust/tree/main").is_none());
+    }
+
+    #[test]
+    fn test_parse_tree_url_handles_repo_root() {
+        let tree = parse_tree_url("https://github.com/rust-lang/rust/tree/main").unwrap();
+        assert_eq!(tree.branch, "main");
+        assert_eq!(tree.path, "");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_listing_lists_directories_before_files() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/rust-lang/rust/contents/src/tools"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"name": "cargo.rs", "type": "file", "size": 42, "html_url": "https://github.com/rust-lang/rust/blob/main/src/tools/cargo.rs"},
+                {"name": "clippy", "type": "dir", "size": 0, "html_url": "https://github.com/rust-lang/rust/tree/main/src/tools/clippy"}
+            ])))
+            .mount(&mock_server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let tree = TreeRef {
+            owner: "rust-lang".to_string(),
+            repo: "rust".to_string(),
+            branch: "main".to_string(),
+            path: "src/tools".to_string(),
+        };
+        let listing = fetch_listing_at(&client, &mock_server.uri(), &tree, None)
+            .await
+            .unwrap();
+
+        let clippy_pos = listing.find("clippy").unwrap();
+        let cargo_pos = listing.find("cargo.rs").unwrap();
+        assert!(
+            clippy_pos < cargo_pos,
+            "directories should be listed before files:\n{listing}"
+        );
+        assert!(listing.contains("directory"));
+        assert!(listing.contains("https://github.com/rust-lang/rust/blob/main/src/tools/cargo.rs"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_listing_returns_none_on_error_response() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/rust-lang/rust/contents/src/tools"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let tree = TreeRef {
+            owner: "rust-lang".to_string(),
+            repo: "rust".to_string(),
+            branch: "main".to_string(),
+            path: "src/tools".to_string(),
+        };
+        assert!(
+            fetch_listing_at(&client, &mock_server.uri(), &tree, None)
+                .await
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_scrape_listing_from_html_parses_embedded_payload() {
+        let html = r#"<html><body><script type="application/json" data-target="react-app.embeddedData">{"payload":{"tree":{"items":[{"name":"clippy","path":"src/tools/clippy","contentType":"directory"},{"name":"cargo.rs","path":"src/tools/cargo.rs","contentType":"file"}]}}}</script></body></html>"#;
+        let tree = TreeRef {
+            owner: "rust-lang".to_string(),
+            repo: "rust".to_string(),
+            branch: "main".to_string(),
+            path: "src/tools".to_string(),
+        };
+        let listing = scrape_listing_from_html(html, &tree).unwrap();
+
+        let clippy_pos = listing.find("clippy").unwrap();
+        let cargo_pos = listing.find("cargo.rs").unwrap();
+        assert!(clippy_pos < cargo_pos);
+        assert!(listing.contains("https://github.com/rust-lang/rust/tree/main/src/tools/clippy"));
+        assert!(listing.contains("https://github.com/rust-lang/rust/blob/main/src/tools/cargo.rs"));
+    }
+
+    #[test]
+    fn test_scrape_listing_from_html_returns_none_without_payload() {
+        let html = "<html><body>no embedded data here</body></html>";
+        let tree = TreeRef {
+            owner: "rust-lang".to_string(),
+            repo: "rust".to_string(),
+            branch: "main".to_string(),
+            path: "src/tools".to_string(),
+        };
+        assert!(scrape_listing_from_html(html, &tree).is_none());
+    }
+}
@@ -0,0 +1,106 @@
+//! Minimal liveness-probe HTTP server for container orchestrators, enabled
+//! via `--health-port`.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+
+fn render_ok(cache_dir: &Path) -> String {
+    format!(
+        r#"{{"status":"ok","cache_dir":"{}","version":"{}"}}"#,
+        cache_dir.display(),
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
+fn render_degraded(error: &str) -> String {
+    format!(r#"{{"status":"degraded","error":"{error}"}}"#)
+}
+
+/// Serves `GET /health` on `addr` until the process exits. Reports the cache
+/// directory as degraded (HTTP 503) if it's no longer accessible. Any other
+/// path gets a 404. Intended to be spawned as a background task from `main`.
+pub async fn serve(addr: std::net::SocketAddr, cache_dir: Arc<PathBuf>) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let cache_dir = cache_dir.clone();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            if tokio::io::AsyncReadExt::read(&mut stream, &mut buf)
+                .await
+                .is_err()
+            {
+                return;
+            }
+            let request = String::from_utf8_lossy(&buf);
+            if !request.starts_with("GET /health") {
+                let _ = stream
+                    .write_all(b"HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\n\r\n")
+                    .await;
+                return;
+            }
+            let (status_line, body) = match tokio::fs::metadata(cache_dir.as_path()).await {
+                Ok(_) => ("HTTP/1.1 200 OK", render_ok(&cache_dir)),
+                Err(e) => (
+                    "HTTP/1.1 503 Service Unavailable",
+                    render_degraded(&e.to_string()),
+                ),
+            };
+            let response = format!(
+                "{status_line}\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_health_endpoint_reports_ok_for_existing_cache_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache_dir = Arc::new(temp_dir.path().to_path_buf());
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let listener = TcpListener::from_std(listener).unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            tokio::io::AsyncReadExt::read(&mut stream, &mut buf)
+                .await
+                .unwrap();
+            let (status_line, body) = match tokio::fs::metadata(cache_dir.as_path()).await {
+                Ok(_) => ("HTTP/1.1 200 OK", render_ok(&cache_dir)),
+                Err(e) => (
+                    "HTTP/1.1 503 Service Unavailable",
+                    render_degraded(&e.to_string()),
+                ),
+            };
+            let response = format!(
+                "{status_line}\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let response = reqwest::get(format!("http://{addr}/health")).await.unwrap();
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["status"], "ok");
+    }
+
+    #[test]
+    fn test_render_degraded_includes_error_message() {
+        let body = render_degraded("cache directory not found");
+        assert!(body.contains(r#""status":"degraded""#));
+        assert!(body.contains("cache directory not found"));
+    }
+}
@@ -0,0 +1,119 @@
+//! Extraction of Schema.org structured data from embedded JSON-LD
+//! (`<script type="application/ld+json">`) for `FetchInput.extract_json_ld`.
+
+use std::collections::HashMap;
+
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+/// Flattened metadata keys pulled out of a JSON-LD object, if present.
+const WANTED_FIELDS: &[&str] = &[
+    "@type",
+    "name",
+    "description",
+    "datePublished",
+    "breadcrumb",
+];
+
+/// Scrapes every `application/ld+json` script block out of `html`, parses
+/// each as JSON, and flattens the fields in `WANTED_FIELDS` into a single
+/// map. Later script blocks win on key collisions. Non-object values (e.g.
+/// a `breadcrumb` that's itself an object) are stringified with
+/// `Value::to_string`. Malformed JSON in a given block is skipped rather
+/// than failing the whole extraction.
+pub fn extract_json_ld(html: &str) -> HashMap<String, String> {
+    let selector = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+    let document = Html::parse_document(html);
+
+    let mut metadata = HashMap::new();
+    for script in document.select(&selector) {
+        let Ok(value) = serde_json::from_str::<Value>(&script.text().collect::<String>()) else {
+            continue;
+        };
+        let Value::Object(object) = value else {
+            continue;
+        };
+        for &field in WANTED_FIELDS {
+            if let Some(v) = object.get(field) {
+                let rendered = match v {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                metadata.insert(field.to_string(), rendered);
+            }
+        }
+    }
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_simple_fields() {
+        let html = r#"
+            <html><head>
+            <script type="application/ld+json">
+            {"@type": "Article", "name": "Getting Started", "datePublished": "2024-01-01"}
+            </script>
+            </head></html>
+        "#;
+        let metadata = extract_json_ld(html);
+        assert_eq!(metadata.get("@type"), Some(&"Article".to_string()));
+        assert_eq!(metadata.get("name"), Some(&"Getting Started".to_string()));
+        assert_eq!(
+            metadata.get("datePublished"),
+            Some(&"2024-01-01".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ignores_unwanted_fields() {
+        let html = r#"
+            <script type="application/ld+json">
+            {"@type": "Article", "unrelatedField": "ignored"}
+            </script>
+        "#;
+        let metadata = extract_json_ld(html);
+        assert!(!metadata.contains_key("unrelatedField"));
+    }
+
+    #[test]
+    fn test_no_script_block_returns_empty() {
+        let html = "<html><body><p>No structured data here.</p></body></html>";
+        assert!(extract_json_ld(html).is_empty());
+    }
+
+    #[test]
+    fn test_malformed_json_is_skipped() {
+        let html = r#"<script type="application/ld+json">not valid json</script>"#;
+        assert!(extract_json_ld(html).is_empty());
+    }
+
+    #[test]
+    fn test_non_string_field_is_stringified() {
+        let html = r#"
+            <script type="application/ld+json">
+            {"@type": "Article", "breadcrumb": {"@type": "BreadcrumbList", "itemListElement": []}}
+            </script>
+        "#;
+        let metadata = extract_json_ld(html);
+        assert!(
+            metadata
+                .get("breadcrumb")
+                .unwrap()
+                .contains("BreadcrumbList")
+        );
+    }
+
+    #[test]
+    fn test_later_script_block_wins_on_collision() {
+        let html = r#"
+            <script type="application/ld+json">{"name": "First"}</script>
+            <script type="application/ld+json">{"name": "Second"}</script>
+        "#;
+        let metadata = extract_json_ld(html);
+        assert_eq!(metadata.get("name"), Some(&"Second".to_string()));
+    }
+}
@@ -0,0 +1,108 @@
+//! Lightweight content-language detection for cached files, so agents
+//! working in English notice when a fetched page turns out to be
+//! localized before wasting context on it. See `FileInfo.language`.
+
+use std::collections::HashMap;
+
+use scraper::{Html, Selector};
+
+/// Below this many characters, `whatlang`'s trigram-based detection is too
+/// unreliable to report, so it's skipped entirely
+const MIN_CHARS_FOR_DETECTION: usize = 200;
+
+/// Detects the dominant language of `text` via `whatlang`, converting its
+/// ISO 639-3 result to ISO 639-1 (`None` for languages with no two-letter
+/// code). Returns `None` for text shorter than `MIN_CHARS_FOR_DETECTION`, or
+/// if `whatlang` can't reach a verdict.
+pub fn detect_language(text: &str) -> Option<(String, f64)> {
+    if text.chars().count() < MIN_CHARS_FOR_DETECTION {
+        return None;
+    }
+    let info = whatlang::detect(text)?;
+    let code = isolang::Language::from_639_3(info.lang().code())?.to_639_1()?;
+    Some((code.to_string(), info.confidence()))
+}
+
+/// Scrapes `<link rel="alternate" hreflang="...">` tags out of `html`,
+/// mapping each advertised language tag to its href. Language tags are
+/// lowercased and truncated to the primary subtag (e.g. `en-US` -> `en`) so
+/// they compare directly against `detect_language`'s ISO 639-1 codes.
+pub fn extract_hreflang_alternates(html: &str) -> HashMap<String, String> {
+    let selector = Selector::parse(r#"link[rel="alternate"][hreflang]"#).unwrap();
+    let document = Html::parse_document(html);
+
+    let mut alternates = HashMap::new();
+    for link in document.select(&selector) {
+        let Some(hreflang) = link.value().attr("hreflang") else {
+            continue;
+        };
+        let Some(href) = link.value().attr("href") else {
+            continue;
+        };
+        let lang = hreflang
+            .split('-')
+            .next()
+            .unwrap_or(hreflang)
+            .to_lowercase();
+        alternates.insert(lang, href.to_string());
+    }
+    alternates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENGLISH: &str = "The quick brown fox jumps over the lazy dog. This sentence is written in English and should be detected as such with reasonably high confidence by any trigram-based language detector relying on common letter sequences.";
+    const JAPANESE: &str = "吾輩は猫である。名前はまだ無い。どこで生れたかとんと見当がつかぬ。何でも薄暗いじめじめした所でニャーニャー泣いていた事だけは記憶している。吾輩はここで始めて人間というものを見た。しかもあとで聞くとそれは人間中で一番獰悪な種族であったそうだ。この書生というのは時々我々を捕えて煮て食うという話である。しかしその当時は何という考もなかったから別に恐しいとも思わなかった。ただ彼の掌に載せられてスーと持ち上げられた時何だかフワフワした感じがあったばかりである。";
+    const MIXED: &str = "Hello world, this is English text. こんにちは世界、これは日本語のテキストです。吾輩は猫である。名前はまだ無い。Mixed-language content like this is common on bilingual documentation sites that ship parallel translations side by side on the same page, often confusing naive detectors entirely.";
+
+    #[test]
+    fn test_detects_english() {
+        let (lang, confidence) = detect_language(ENGLISH).unwrap();
+        assert_eq!(lang, "en");
+        assert!(confidence > 0.0);
+    }
+
+    #[test]
+    fn test_detects_japanese() {
+        let (lang, _) = detect_language(JAPANESE).unwrap();
+        assert_eq!(lang, "ja");
+    }
+
+    #[test]
+    fn test_mixed_language_still_returns_a_verdict() {
+        // Mixed-language input has no "correct" answer, but detection should
+        // still return some verdict rather than panicking.
+        assert!(detect_language(MIXED).is_some());
+    }
+
+    #[test]
+    fn test_skips_short_documents() {
+        assert!(detect_language("Too short").is_none());
+    }
+
+    #[test]
+    fn test_extracts_hreflang_alternates() {
+        let html = r#"
+            <html><head>
+            <link rel="alternate" hreflang="en-US" href="https://example.com/en/page">
+            <link rel="alternate" hreflang="zh-CN" href="https://example.com/zh/page">
+            </head></html>
+        "#;
+        let alternates = extract_hreflang_alternates(html);
+        assert_eq!(
+            alternates.get("en"),
+            Some(&"https://example.com/en/page".to_string())
+        );
+        assert_eq!(
+            alternates.get("zh"),
+            Some(&"https://example.com/zh/page".to_string())
+        );
+    }
+
+    #[test]
+    fn test_no_hreflang_tags_returns_empty() {
+        assert!(extract_hreflang_alternates("<html><body>No alternates</body></html>").is_empty());
+    }
+}
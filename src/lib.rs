@@ -1 +1,6 @@
+mod admonitions;
+pub mod content;
+pub mod converter;
+pub mod sanitize;
+mod tables;
 pub mod toc;
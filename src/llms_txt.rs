@@ -0,0 +1,79 @@
+//! Extraction of document links from an `llms.txt`/`llms-full.txt` index,
+//! used by `FetchInput.follow_llms_txt` to discover markdown documents worth
+//! fetching in addition to the index itself.
+
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use url::Url;
+
+/// Extracts every markdown link target in `markdown`, resolved against
+/// `base_url`, keeping only links whose path ends in `.md` (an `llms.txt`
+/// index links to the actual docs this way) and deduplicating while
+/// preserving first-seen order. Returns an empty list if `base_url` doesn't
+/// parse.
+pub fn extract_markdown_links(markdown: &str, base_url: &str) -> Vec<String> {
+    let Ok(base) = Url::parse(base_url) else {
+        return Vec::new();
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    let mut links = Vec::new();
+    for event in Parser::new_ext(markdown, Options::empty()) {
+        let Event::Start(Tag::Link { dest_url, .. }) = event else {
+            continue;
+        };
+        let Ok(target) = base.join(&dest_url) else {
+            continue;
+        };
+        if !target.path().to_ascii_lowercase().ends_with(".md") {
+            continue;
+        }
+        let target = target.to_string();
+        if seen.insert(target.clone()) {
+            links.push(target);
+        }
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_relative_md_links() {
+        let markdown = "# Docs\n\n- [Guide](/docs/guide.md)\n- [API](/docs/api.md)\n";
+        assert_eq!(
+            extract_markdown_links(markdown, "https://example.com/llms.txt"),
+            vec![
+                "https://example.com/docs/guide.md",
+                "https://example.com/docs/api.md",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignores_non_markdown_links() {
+        let markdown = "- [Guide](/docs/guide.md)\n- [Home](/index.html)\n- [Repo](https://github.com/example/repo)\n";
+        assert_eq!(
+            extract_markdown_links(markdown, "https://example.com/llms.txt"),
+            vec!["https://example.com/docs/guide.md"]
+        );
+    }
+
+    #[test]
+    fn test_deduplicates_repeated_links() {
+        let markdown = "- [Guide](/docs/guide.md)\n- [Guide again](/docs/guide.md)\n";
+        assert_eq!(
+            extract_markdown_links(markdown, "https://example.com/llms.txt"),
+            vec!["https://example.com/docs/guide.md"]
+        );
+    }
+
+    #[test]
+    fn test_invalid_base_url_returns_empty() {
+        assert_eq!(
+            extract_markdown_links("- [Guide](/docs/guide.md)\n", "not a url"),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Handles LaTeX math notation (`\( \)`/`\[ \]` delimiters, `$...$`/`$$...$$`)
+//! left behind in math-heavy documentation (Numpy, `SciPy`, ML papers) after
+//! `html2md` conversion, controlled per-call via `FetchInput.convert_math`.
+
+use std::sync::LazyLock;
+
+use phf::phf_map;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Controls how `FetchInput.convert_math` handles LaTeX math notation in the
+/// converted markdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MathMode {
+    /// Leaves LaTeX delimiters and commands as-is
+    #[default]
+    Preserve,
+    /// Converts simple LaTeX commands (e.g. `\alpha`) to their Unicode
+    /// symbol via `convert_math_unicode`, leaving anything not in the
+    /// lookup table untouched
+    Unicode,
+    /// Strips `\( \)`, `\[ \]`, `$...$`, and `$$...$$` math blocks entirely
+    Omit,
+}
+
+/// Applies `mode` to `content`, dispatching to `convert_math_unicode` or
+/// `omit_math` as appropriate. Called from `fetch_impl` after markdown
+/// cleanup, alongside `normalize_whitespace`/`strip_anchor_links`.
+pub fn apply_math_mode(content: &str, mode: MathMode) -> String {
+    match mode {
+        MathMode::Preserve => content.to_string(),
+        MathMode::Unicode => convert_math_unicode(content),
+        MathMode::Omit => omit_math(content),
+    }
+}
+
+/// LaTeX command -> Unicode symbol lookup table for `convert_math_unicode`,
+/// covering the Greek letters and operators common in math-heavy docs.
+/// Anything not listed here is left as-is rather than guessed at.
+static LATEX_SYMBOLS: phf::Map<&'static str, &'static str> = phf_map! {
+    "\\alpha" => "α",
+    "\\beta" => "β",
+    "\\gamma" => "γ",
+    "\\delta" => "δ",
+    "\\epsilon" => "ε",
+    "\\zeta" => "ζ",
+    "\\eta" => "η",
+    "\\theta" => "θ",
+    "\\lambda" => "λ",
+    "\\mu" => "μ",
+    "\\nu" => "ν",
+    "\\pi" => "π",
+    "\\rho" => "ρ",
+    "\\sigma" => "σ",
+    "\\tau" => "τ",
+    "\\phi" => "φ",
+    "\\chi" => "χ",
+    "\\psi" => "ψ",
+    "\\omega" => "ω",
+    "\\Delta" => "Δ",
+    "\\Sigma" => "Σ",
+    "\\Omega" => "Ω",
+    "\\infty" => "∞",
+    "\\leq" => "≤",
+    "\\geq" => "≥",
+    "\\neq" => "≠",
+    "\\approx" => "≈",
+    "\\times" => "×",
+    "\\cdot" => "·",
+    "\\pm" => "±",
+    "\\sum" => "∑",
+    "\\prod" => "∏",
+    "\\int" => "∫",
+    "\\sqrt" => "√",
+    "\\partial" => "∂",
+    "\\nabla" => "∇",
+    "\\forall" => "∀",
+    "\\exists" => "∃",
+    "\\in" => "∈",
+    "\\subset" => "⊂",
+    "\\cup" => "∪",
+    "\\cap" => "∩",
+    "\\rightarrow" => "→",
+    "\\leftarrow" => "←",
+};
+
+/// Matches a LaTeX command name (backslash plus letters), the unit
+/// `convert_math_unicode` looks up in `LATEX_SYMBOLS`.
+static LATEX_COMMAND: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\\[a-zA-Z]+").unwrap());
+
+/// Replaces every LaTeX command in `content` found in `LATEX_SYMBOLS` with
+/// its Unicode symbol (e.g. `\alpha` -> `α`). Commands outside the lookup
+/// table (anything beyond simple Greek letters and common operators) are
+/// left untouched rather than guessed at.
+pub fn convert_math_unicode(content: &str) -> String {
+    LATEX_COMMAND
+        .replace_all(content, |caps: &regex::Captures<'_>| {
+            LATEX_SYMBOLS
+                .get(&caps[0])
+                .copied()
+                .unwrap_or(&caps[0])
+                .to_string()
+        })
+        .into_owned()
+}
+
+/// Matches `\( \)`, `\[ \]`, `$$...$$`, and `$...$` math blocks, in that
+/// priority order so `$$...$$` isn't mistaken for two `$...$` spans.
+static MATH_BLOCK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)\\\(.*?\\\)|\\\[.*?\\\]|\$\$.*?\$\$|\$[^\$\n]+\$").unwrap());
+
+/// Strips every `\( \)`, `\[ \]`, `$...$`, and `$$...$$` math block from
+/// `content` entirely, for documentation where the raw LaTeX source is more
+/// noise than signal without a renderer.
+pub fn omit_math(content: &str) -> String {
+    MATH_BLOCK.replace_all(content, "").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preserve_leaves_content_untouched() {
+        let content = r"Let \(\alpha\) be the learning rate.";
+        assert_eq!(apply_math_mode(content, MathMode::Preserve), content);
+    }
+
+    #[test]
+    fn test_unicode_converts_known_commands() {
+        let content = r"Let \alpha be the learning rate and \beta the decay.";
+        assert_eq!(
+            convert_math_unicode(content),
+            "Let α be the learning rate and β the decay."
+        );
+    }
+
+    #[test]
+    fn test_unicode_leaves_unknown_commands_untouched() {
+        let content = r"The \operatorname{argmax} over \theta.";
+        assert_eq!(
+            convert_math_unicode(content),
+            "The \\operatorname{argmax} over θ."
+        );
+    }
+
+    #[test]
+    fn test_omit_strips_inline_and_display_delimiters() {
+        let content = "Given \\(x^2\\), the full form is \\[x^2 + y^2 = z^2\\] by Pythagoras.";
+        assert_eq!(
+            omit_math(content),
+            "Given , the full form is  by Pythagoras."
+        );
+    }
+
+    #[test]
+    fn test_omit_strips_dollar_delimiters() {
+        let content = "Inline $x + y$ and display $$\\int_0^1 f(x)\\,dx$$ forms.";
+        assert_eq!(omit_math(content), "Inline  and display  forms.");
+    }
+
+    #[test]
+    fn test_apply_math_mode_dispatches_to_omit() {
+        let content = r"Let \(\alpha\) be the learning rate.";
+        assert_eq!(
+            apply_math_mode(content, MathMode::Omit),
+            "Let  be the learning rate."
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Converts ` ```mermaid ` code blocks to ASCII diagrams, enabled via
+//! `--features mermaid`. Shells out to a local `mmdc` (mermaid-cli) install;
+//! a block is left untouched if `mmdc` isn't on `PATH` or fails on that
+//! particular diagram.
+
+use std::io::{Read as _, Write as _};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+
+/// Upper bound on how long a single diagram's `mmdc` invocation may run
+/// before it's killed. `render_mermaid_blocks` already runs inside
+/// `tokio::task::spawn_blocking`, so this only protects against a single
+/// hung `mmdc` process tying up that blocking thread indefinitely, not the
+/// async runtime itself - but a bounded wait is still needed so a stuck
+/// render doesn't outlive the call that triggered it.
+const MMDC_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Byte range of each ` ```mermaid ` block (fences included), paired with
+/// its diagram source (fences stripped).
+fn mermaid_blocks(markdown: &str) -> Vec<(std::ops::Range<usize>, String)> {
+    let mut blocks = Vec::new();
+    let mut current_start: Option<usize> = None;
+    let mut current_is_mermaid = false;
+
+    for (event, range) in Parser::new_ext(markdown, Options::all()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang))) => {
+                current_start = Some(range.start);
+                current_is_mermaid = lang.as_ref() == "mermaid";
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some(start) = current_start.take()
+                    && current_is_mermaid
+                    && let Some(diagram) = fence_body(&markdown[start..range.end])
+                {
+                    blocks.push((start..range.end, diagram));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    blocks
+}
+
+/// Strips the opening and closing fence lines from a fenced code block's
+/// source text.
+fn fence_body(fenced: &str) -> Option<String> {
+    let mut lines: Vec<&str> = fenced.lines().collect();
+    if lines.len() < 2 {
+        return None;
+    }
+    lines.remove(0);
+    lines.pop();
+    Some(lines.join("\n"))
+}
+
+/// Runs `diagram` through `mmdc --outputFormat ascii`, returning `None` if
+/// `mmdc` isn't installed, exits non-zero, produces empty output, or is
+/// still running after `MMDC_TIMEOUT` (in which case it's killed).
+fn render_ascii(diagram: &str) -> Option<String> {
+    let mut child = Command::new("mmdc")
+        .args(["--input", "-", "--output", "-", "--outputFormat", "ascii"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    child.stdin.take()?.write_all(diagram.as_bytes()).ok()?;
+
+    // Drained on its own thread rather than after `wait()` returns, so a
+    // diagram large enough to fill the stdout pipe buffer before `mmdc`
+    // exits can't deadlock the poll loop below against a child blocked on a
+    // full pipe.
+    let mut stdout = child.stdout.take()?;
+    let reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        stdout.read_to_end(&mut buf).ok();
+        buf
+    });
+
+    let deadline = Instant::now() + MMDC_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if Instant::now() < deadline => {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            Err(_) => break None,
+        }
+    }?;
+
+    let stdout_bytes = reader.join().ok()?;
+    if !status.success() {
+        return None;
+    }
+
+    let ascii = String::from_utf8(stdout_bytes).ok()?;
+    if ascii.trim().is_empty() {
+        None
+    } else {
+        Some(ascii)
+    }
+}
+
+/// Replaces each ` ```mermaid ` block in `markdown` with its ASCII
+/// rendering. Blocks that can't be rendered (most commonly because `mmdc`
+/// isn't installed) are left as the original Mermaid code block.
+pub fn render_mermaid_blocks(markdown: &str) -> String {
+    let blocks = mermaid_blocks(markdown);
+    if blocks.is_empty() {
+        return markdown.to_string();
+    }
+
+    let mut result = String::with_capacity(markdown.len());
+    let mut last_end = 0;
+    for (range, diagram) in blocks {
+        result.push_str(&markdown[last_end..range.start]);
+        match render_ascii(&diagram) {
+            Some(ascii) => {
+                result.push_str("<!-- rendered from a mermaid diagram -->\n```\n");
+                result.push_str(ascii.trim_end());
+                result.push_str("\n```");
+            }
+            None => result.push_str(&markdown[range.clone()]),
+        }
+        last_end = range.end;
+    }
+    result.push_str(&markdown[last_end..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leaves_non_mermaid_blocks_untouched() {
+        let md = "# Title\n\n```rust\nfn main() {}\n```\n";
+        assert_eq!(render_mermaid_blocks(md), md);
+    }
+
+    #[test]
+    fn test_leaves_mermaid_block_untouched_when_mmdc_missing() {
+        // mmdc isn't installed in this environment, so the fallback path is
+        // what actually runs here.
+        let md = "# Title\n\n```mermaid\ngraph TD;\nA-->B;\n```\n\nmore text";
+        assert_eq!(render_mermaid_blocks(md), md);
+    }
+
+    #[test]
+    fn test_fence_body_strips_fence_lines() {
+        let fenced = "```mermaid\ngraph TD;\nA-->B;\n```";
+        assert_eq!(fence_body(fenced).unwrap(), "graph TD;\nA-->B;");
+    }
+}
@@ -0,0 +1,102 @@
+//! Prometheus-style counters for the `fetch` tool, served over a plain
+//! `/metrics` HTTP endpoint when `--metrics-port` is set.
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+
+/// Counters incremented by `FetchServer::fetch`. Cheap, lock-free, and safe
+/// to share across concurrent tool calls via `Arc`.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    pub requests_success: AtomicU64,
+    pub requests_http_error: AtomicU64,
+    pub requests_network_error: AtomicU64,
+    pub requests_redirect_loop: AtomicU64,
+    pub requests_empty_body: AtomicU64,
+    pub requests_probe_skipped: AtomicU64,
+    pub cache_writes_total: AtomicU64,
+    pub bytes_fetched_total: AtomicU64,
+    pub bytes_saved_total: AtomicU64,
+}
+
+impl Metrics {
+    fn render(&self) -> String {
+        format!(
+            "fetch_requests_total{{status=\"success\"}} {}\n\
+             fetch_requests_total{{status=\"http_error\"}} {}\n\
+             fetch_requests_total{{status=\"network_error\"}} {}\n\
+             fetch_requests_total{{status=\"redirect_loop\"}} {}\n\
+             fetch_requests_total{{status=\"empty_body\"}} {}\n\
+             fetch_requests_total{{status=\"probe_skipped\"}} {}\n\
+             fetch_cache_writes_total {}\n\
+             fetch_bytes_fetched_total {}\n\
+             fetch_bytes_saved_total {}\n",
+            self.requests_success.load(Ordering::Relaxed),
+            self.requests_http_error.load(Ordering::Relaxed),
+            self.requests_network_error.load(Ordering::Relaxed),
+            self.requests_redirect_loop.load(Ordering::Relaxed),
+            self.requests_empty_body.load(Ordering::Relaxed),
+            self.requests_probe_skipped.load(Ordering::Relaxed),
+            self.cache_writes_total.load(Ordering::Relaxed),
+            self.bytes_fetched_total.load(Ordering::Relaxed),
+            self.bytes_saved_total.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// Serves `GET /metrics` on `addr` until the process exits. Any other path
+/// gets a 404. Intended to be spawned as a background task from `main`.
+pub async fn serve(addr: SocketAddr, metrics: std::sync::Arc<Metrics>) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            if tokio::io::AsyncReadExt::read(&mut stream, &mut buf)
+                .await
+                .is_err()
+            {
+                return;
+            }
+            let request = String::from_utf8_lossy(&buf);
+            let body = if request.starts_with("GET /metrics") {
+                metrics.render()
+            } else {
+                let _ = stream
+                    .write_all(b"HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\n\r\n")
+                    .await;
+                return;
+            };
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: text/plain; version=0.0.4\r\ncontent-length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters() {
+        let metrics = Metrics::default();
+        metrics.requests_success.store(3, Ordering::Relaxed);
+        metrics.requests_http_error.store(1, Ordering::Relaxed);
+        metrics.cache_writes_total.store(2, Ordering::Relaxed);
+
+        let rendered = metrics.render();
+        assert!(rendered.contains("fetch_requests_total{status=\"success\"} 3"));
+        assert!(rendered.contains("fetch_requests_total{status=\"http_error\"} 1"));
+        assert!(rendered.contains("fetch_cache_writes_total 2"));
+        assert!(rendered.contains("fetch_requests_total{status=\"network_error\"} 0"));
+        assert!(rendered.contains("fetch_requests_total{status=\"empty_body\"} 0"));
+        assert!(rendered.contains("fetch_requests_total{status=\"probe_skipped\"} 0"));
+    }
+}
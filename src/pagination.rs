@@ -0,0 +1,94 @@
+//! Detection of "next page" links for documentation split across several
+//! paginated pages, used by `FetchInput.follow_pagination` to fetch and
+//! concatenate each page into a single cached file.
+
+use scraper::{Html, Selector};
+use url::Url;
+
+/// CSS classes/rels, beyond `<link rel="next">`, that sites commonly use on
+/// an anchor pointing at the next page of a paginated document.
+const NEXT_ANCHOR_SELECTORS: &[&str] = &["a.pagination-next", r#"a[rel="next"]"#, "a.next"];
+
+/// Finds the next page's URL, preferring a `<link rel="next" href>` in the
+/// document head, then falling back to the first matching anchor in
+/// `NEXT_ANCHOR_SELECTORS`. Resolves the target against `base_url` and keeps
+/// only same-host links (cross-host "next" links are usually ads or related
+/// sites, not a continuation of the same document). Returns `None` if `html`
+/// has no next link, or it resolves to a different host than `base_url`.
+pub fn find_next_page(html: &str, base_url: &str) -> Option<String> {
+    let Ok(base) = Url::parse(base_url) else {
+        return None;
+    };
+    let document = Html::parse_document(html);
+
+    let link_selector = Selector::parse(r#"link[rel="next"]"#).ok()?;
+    let href = document
+        .select(&link_selector)
+        .find_map(|link| link.value().attr("href"))
+        .or_else(|| {
+            NEXT_ANCHOR_SELECTORS.iter().find_map(|raw_selector| {
+                let selector = Selector::parse(raw_selector).ok()?;
+                document
+                    .select(&selector)
+                    .find_map(|a| a.value().attr("href"))
+            })
+        })?;
+
+    let target = base.join(href).ok()?;
+    if target.host_str() != base.host_str() {
+        return None;
+    }
+
+    Some(target.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_link_rel_next() {
+        let html = r#"<html><head><link rel="next" href="/docs/page-2"></head></html>"#;
+        assert_eq!(
+            find_next_page(html, "https://docs.example.com/docs/page-1"),
+            Some("https://docs.example.com/docs/page-2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_falls_back_to_pagination_next_anchor() {
+        let html =
+            r#"<html><body><a class="pagination-next" href="page-2.html">Next</a></body></html>"#;
+        assert_eq!(
+            find_next_page(html, "https://docs.example.com/guide/page-1.html"),
+            Some("https://docs.example.com/guide/page-2.html".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ignores_cross_host_next_link() {
+        let html = r#"<link rel="next" href="https://other.example.com/page-2">"#;
+        assert_eq!(
+            find_next_page(html, "https://docs.example.com/page-1"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_no_next_link_returns_none() {
+        let html = "<html><body><p>Last page, no more links.</p></body></html>";
+        assert_eq!(
+            find_next_page(html, "https://docs.example.com/page-3"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_relative_href_resolved_against_base() {
+        let html = r#"<a rel="next" href="../chapter-2/index.html">Next chapter</a>"#;
+        assert_eq!(
+            find_next_page(html, "https://docs.example.com/book/chapter-1/index.html"),
+            Some("https://docs.example.com/book/chapter-2/index.html".to_string())
+        );
+    }
+}
@@ -0,0 +1,33 @@
+//! Extracts plain text from PDF response bodies, enabled via `--features pdf`.
+//! Compiled out entirely otherwise, in which case PDFs are still cached but
+//! with a placeholder note (see `FetchServer::convert_result_content`).
+
+use pdf_extract::extract_text_from_mem;
+
+/// Extracts the text content of a PDF's raw bytes. Errors (encrypted,
+/// corrupt, or otherwise unparseable PDFs) are surfaced as a message rather
+/// than panicking, so the caller can report the failure instead of caching
+/// garbage.
+pub fn extract_text(bytes: &[u8]) -> Result<String, String> {
+    extract_text_from_mem(bytes).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A minimal single-page PDF containing the text "Hello PDF", built by
+    // hand rather than pulled in as a binary fixture file.
+    const MINIMAL_PDF: &[u8] = include_bytes!("../test-fixtures/minimal.pdf");
+
+    #[test]
+    fn test_extracts_text_from_minimal_pdf() {
+        let text = extract_text(MINIMAL_PDF).unwrap();
+        assert!(text.contains("Hello PDF"), "{text}");
+    }
+
+    #[test]
+    fn test_errors_on_non_pdf_bytes() {
+        assert!(extract_text(b"not a pdf").is_err());
+    }
+}
@@ -0,0 +1,247 @@
+//! `robots.txt` fetching, caching, and rule matching, used by
+//! `FetchInput.respect_robots_txt` to skip URLs a site's `robots.txt`
+//! disallows before fetching them.
+
+use std::path::Path;
+use std::time::Duration;
+
+use regex::Regex;
+use tokio::fs;
+
+use crate::sanitize_host_for_path;
+
+/// Product token this server identifies as in `User-Agent` headers (see
+/// `fetch_url`), checked against `robots.txt` `User-agent` lines before
+/// falling back to the `User-agent: *` group.
+const USER_AGENT_TOKEN: &str = "llms-fetch-mcp";
+
+/// How long a cached `robots.txt` is trusted before being re-fetched.
+const ROBOTS_CACHE_TTL: Duration = Duration::from_hours(1);
+
+/// Returns `false` only if `url`'s host publishes a `robots.txt` that
+/// disallows `USER_AGENT_TOKEN` (falling back to `User-agent: *`) from the
+/// URL's path. A missing, unfetchable, or unparseable `robots.txt` is
+/// treated as allow-everything, matching the usual crawler convention.
+pub async fn is_allowed(client: &reqwest::Client, cache_dir: &Path, url: &str) -> bool {
+    let Ok(parsed) = url::Url::parse(url) else {
+        return true;
+    };
+    let Some(host) = parsed.host_str() else {
+        return true;
+    };
+
+    let Ok(robots_url) = parsed.join("/robots.txt") else {
+        return true;
+    };
+    let Some(robots_txt) = fetch_robots_txt(client, cache_dir, host, robots_url.as_str()).await
+    else {
+        return true;
+    };
+
+    let path = match parsed.query() {
+        Some(query) => format!("{}?{query}", parsed.path()),
+        None => parsed.path().to_string(),
+    };
+
+    path_is_allowed(&robots_txt, &path)
+}
+
+/// Fetches `robots_url` (the target host's `/robots.txt`), serving a cached
+/// copy from `{cache_dir}/{host}/robots.txt` if it's younger than
+/// `ROBOTS_CACHE_TTL`. Returns `None` on a missing file, a non-2xx response,
+/// or a network error.
+async fn fetch_robots_txt(
+    client: &reqwest::Client,
+    cache_dir: &Path,
+    host: &str,
+    robots_url: &str,
+) -> Option<String> {
+    let cache_path = cache_dir
+        .join(sanitize_host_for_path(host))
+        .join("robots.txt");
+
+    if let Ok(metadata) = fs::metadata(&cache_path).await
+        && let Ok(age) = metadata
+            .modified()
+            .and_then(|m| m.elapsed().map_err(std::io::Error::other))
+        && age < ROBOTS_CACHE_TTL
+        && let Ok(cached) = fs::read_to_string(&cache_path).await
+    {
+        return Some(cached);
+    }
+
+    let response = client.get(robots_url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let robots_txt = response.text().await.ok()?;
+
+    if let Some(parent) = cache_path.parent() {
+        let _ = fs::create_dir_all(parent).await;
+    }
+    let _ = fs::write(&cache_path, &robots_txt).await;
+
+    Some(robots_txt)
+}
+
+/// Checks `path` against `robots_txt`'s rules for `USER_AGENT_TOKEN`,
+/// falling back to the `User-agent: *` group. Per the de-facto robots.txt
+/// convention, the most specific (longest pattern) matching rule wins; ties
+/// go to `Allow`. A `robots.txt` with no matching group allows everything.
+fn path_is_allowed(robots_txt: &str, path: &str) -> bool {
+    let rules =
+        rules_for_agent(robots_txt, USER_AGENT_TOKEN).or_else(|| rules_for_agent(robots_txt, "*"));
+    let Some(rules) = rules else {
+        return true;
+    };
+
+    let mut best: Option<(usize, bool)> = None;
+    for (pattern, is_allow) in &rules {
+        if !pattern_regex(pattern).is_match(path) {
+            continue;
+        }
+        let specificity = pattern.len();
+        let replace = match best {
+            None => true,
+            Some((best_len, best_allow)) => {
+                specificity > best_len || (specificity == best_len && *is_allow && !best_allow)
+            }
+        };
+        if replace {
+            best = Some((specificity, *is_allow));
+        }
+    }
+
+    best.is_none_or(|(_, is_allow)| is_allow)
+}
+
+/// Extracts the `(path_pattern, is_allow)` rules from the first group in
+/// `robots_txt` whose `User-agent` line matches `agent` (case-insensitively).
+/// Consecutive `User-agent` lines share one group; `Allow`/`Disallow` lines
+/// close the group's agent list and attach to it. Returns `None` if no group
+/// names `agent`. An empty `Disallow` value means "disallow nothing", the
+/// standard robots.txt idiom for an explicit allow-all group.
+fn rules_for_agent(robots_txt: &str, agent: &str) -> Option<Vec<(String, bool)>> {
+    #[derive(Default)]
+    struct Group {
+        agents: Vec<String>,
+        rules: Vec<(String, bool)>,
+    }
+
+    let mut groups: Vec<Group> = Vec::new();
+    let mut current: Option<Group> = None;
+    let mut awaiting_agents = true;
+
+    for raw_line in robots_txt.lines() {
+        let line = raw_line.split('#').next().unwrap_or("").trim();
+        let Some((directive, value)) = line.split_once(':') else {
+            continue;
+        };
+        let directive = directive.trim().to_ascii_lowercase();
+        let value = value.trim();
+
+        match directive.as_str() {
+            "user-agent" => {
+                if !awaiting_agents {
+                    groups.extend(current.take());
+                    awaiting_agents = true;
+                }
+                current
+                    .get_or_insert_with(Group::default)
+                    .agents
+                    .push(value.to_ascii_lowercase());
+            }
+            "disallow" => {
+                awaiting_agents = false;
+                if let Some(group) = &mut current {
+                    let rule = if value.is_empty() {
+                        (String::new(), true)
+                    } else {
+                        (value.to_string(), false)
+                    };
+                    group.rules.push(rule);
+                }
+            }
+            "allow" => {
+                awaiting_agents = false;
+                if let Some(group) = &mut current {
+                    group.rules.push((value.to_string(), true));
+                }
+            }
+            _ => {}
+        }
+    }
+    groups.extend(current);
+
+    let agent_lower = agent.to_ascii_lowercase();
+    groups
+        .into_iter()
+        .find(|group| group.agents.contains(&agent_lower))
+        .map(|group| group.rules)
+}
+
+/// Compiles a robots.txt path pattern into a prefix-anchored regex, where
+/// `*` matches any run of characters and a trailing `$` anchors the end of
+/// the path — the de-facto extensions most real `robots.txt` files rely on,
+/// beyond the original RFC's plain prefix matching.
+fn pattern_regex(pattern: &str) -> Regex {
+    let anchored_end = pattern.ends_with('$');
+    let body = pattern.strip_suffix('$').unwrap_or(pattern);
+
+    let mut regex_str = String::from("^");
+    for (i, part) in body.split('*').enumerate() {
+        if i > 0 {
+            regex_str.push_str(".*");
+        }
+        regex_str.push_str(&regex::escape(part));
+    }
+    if anchored_end {
+        regex_str.push('$');
+    }
+
+    Regex::new(&regex_str).unwrap_or_else(|_| Regex::new("^$").expect("static pattern is valid"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disallowed_path_under_wildcard_group() {
+        let robots = "User-agent: *\nDisallow: /private/\n";
+        assert!(!path_is_allowed(robots, "/private/page"));
+        assert!(path_is_allowed(robots, "/public/page"));
+    }
+
+    #[test]
+    fn test_named_agent_group_takes_priority_over_wildcard() {
+        let robots = "User-agent: llms-fetch-mcp\nAllow: /\n\nUser-agent: *\nDisallow: /\n";
+        assert!(path_is_allowed(robots, "/anything"));
+    }
+
+    #[test]
+    fn test_longest_matching_rule_wins_over_shorter_disallow() {
+        let robots = "User-agent: *\nDisallow: /docs/\nAllow: /docs/public/\n";
+        assert!(path_is_allowed(robots, "/docs/public/guide"));
+        assert!(!path_is_allowed(robots, "/docs/private/guide"));
+    }
+
+    #[test]
+    fn test_empty_disallow_value_allows_everything() {
+        let robots = "User-agent: *\nDisallow:\n";
+        assert!(path_is_allowed(robots, "/anything"));
+    }
+
+    #[test]
+    fn test_no_matching_group_allows_everything() {
+        let robots = "User-agent: OtherBot\nDisallow: /\n";
+        assert!(path_is_allowed(robots, "/anything"));
+    }
+
+    #[test]
+    fn test_wildcard_and_end_anchor_in_pattern() {
+        let robots = "User-agent: *\nDisallow: /*.pdf$\n";
+        assert!(!path_is_allowed(robots, "/files/report.pdf"));
+        assert!(path_is_allowed(robots, "/files/report.pdf.html"));
+    }
+}
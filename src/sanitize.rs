@@ -0,0 +1,348 @@
+//! Selector-based HTML chrome removal applied before Readability extraction,
+//! controlled per-call via `FetchInput.html_sanitize_level` and, per host,
+//! via `--site-config`'s `[clean]` table and `SiteProfile.extra_remove_selectors`
+//! / `SiteProfile.remove_selectors` (see `CleanConfig`).
+
+use schemars::JsonSchema;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+
+/// Controls how aggressively `ReadabilityConverter` strips navigation,
+/// sidebar, and other chrome markup before handing the document to
+/// `dom_smoothie`'s Readability pass. Some documentation pages put
+/// next/previous page links inside `main`, where `Standard` would remove
+/// them along with the real sidebar; `Minimal` avoids that at the cost of
+/// leaving more chrome for Readability's own heuristics to deal with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SanitizeLevel {
+    /// Removes only `<script>`, `<style>`, and `<noscript>` elements
+    Minimal,
+    /// `Minimal`, plus `<nav>`, `<header>`, `<footer>`, and common
+    /// breadcrumb/sidebar class or id patterns
+    #[default]
+    Standard,
+    /// `Standard`, plus `<aside>`, `<figure>`, and `aria-hidden="true"` elements
+    Aggressive,
+}
+
+const MINIMAL_SELECTORS: &[&str] = &["script", "style", "noscript"];
+const STANDARD_SELECTORS: &[&str] = &[
+    "script",
+    "style",
+    "noscript",
+    "nav",
+    "header",
+    "footer",
+    "[class*=\"breadcrumb\"]",
+    "[id*=\"breadcrumb\"]",
+    "[class*=\"sidebar\"]",
+    "[id*=\"sidebar\"]",
+];
+const AGGRESSIVE_SELECTORS: &[&str] = &[
+    "script",
+    "style",
+    "noscript",
+    "nav",
+    "header",
+    "footer",
+    "[class*=\"breadcrumb\"]",
+    "[id*=\"breadcrumb\"]",
+    "[class*=\"sidebar\"]",
+    "[id*=\"sidebar\"]",
+    "aside",
+    "figure",
+    "[aria-hidden=\"true\"]",
+];
+
+/// Cookie/privacy-consent banners from common vendors (Cookiebot, `OneTrust`,
+/// plus generic class/id patterns). These are never real content at any
+/// `SanitizeLevel`, so they're stripped in addition to the level's own
+/// selector set rather than folded into `Minimal`/`Standard`/`Aggressive`.
+const COOKIE_CONSENT_SELECTORS: &[&str] = &[
+    "#CybotCookiebotDialog",
+    "#CybotCookiebotDialogBodyUnderlay",
+    "#onetrust-banner-sdk",
+    "#onetrust-consent-sdk",
+    "#onetrust-pc-sdk",
+    "[id*=\"cookie-banner\" i]",
+    "[id*=\"cookie-consent\" i]",
+    "[id*=\"cookie-notice\" i]",
+    "[class*=\"cookie-banner\" i]",
+    "[class*=\"cookie-consent\" i]",
+    "[class*=\"cookie-notice\" i]",
+    "[class*=\"gdpr-notice\" i]",
+    "[class*=\"gdpr-banner\" i]",
+];
+
+fn selectors_for(level: SanitizeLevel) -> &'static [&'static str] {
+    match level {
+        SanitizeLevel::Minimal => MINIMAL_SELECTORS,
+        SanitizeLevel::Standard => STANDARD_SELECTORS,
+        SanitizeLevel::Aggressive => AGGRESSIVE_SELECTORS,
+    }
+}
+
+/// Checks that `selector` is valid CSS, for validating `--site-config`
+/// selector lists at startup rather than letting a typo silently fail to
+/// match (and so strip nothing) on every fetch to that host.
+pub fn validate_selector(selector: &str) -> Result<(), String> {
+    Selector::parse(selector)
+        .map(|_| ())
+        .map_err(|e| format!("invalid CSS selector {selector:?}: {e}"))
+}
+
+/// Runtime-configurable removal selectors layered on top of this module's
+/// compile-time `MINIMAL_SELECTORS`/`STANDARD_SELECTORS`/`AGGRESSIVE_SELECTORS`
+/// defaults, built from a `--site-config` file's `[clean]` table (global
+/// additions applied to every host) and combined with a `SiteProfile`'s
+/// `extra_remove_selectors` (per-host additions) or `remove_selectors`
+/// (per-host full replacement of the level's defaults) via `resolve`.
+/// `COOKIE_CONSENT_SELECTORS` are always appended regardless of overrides,
+/// same as the unconfigured `strip_chrome`.
+#[derive(Debug, Clone, Default)]
+pub struct CleanConfig {
+    global_extra_remove_selectors: Vec<String>,
+}
+
+impl CleanConfig {
+    /// Validates `global_extra_remove_selectors` up front so an invalid
+    /// selector in `--site-config`'s `[clean]` table is a startup error.
+    pub fn new(global_extra_remove_selectors: Vec<String>) -> Result<Self, String> {
+        for selector in &global_extra_remove_selectors {
+            validate_selector(selector)?;
+        }
+        Ok(Self {
+            global_extra_remove_selectors,
+        })
+    }
+
+    /// Builds the full removal selector list for `level`, starting from
+    /// `host_replace` if given (otherwise `level`'s compile-time defaults),
+    /// then appending this config's global additions, `host_extra`, and
+    /// finally `COOKIE_CONSENT_SELECTORS`.
+    pub fn resolve(
+        &self,
+        level: SanitizeLevel,
+        host_extra: Option<&[String]>,
+        host_replace: Option<&[String]>,
+    ) -> Vec<String> {
+        let mut selectors: Vec<String> = match host_replace {
+            Some(replace) => replace.to_vec(),
+            None => selectors_for(level)
+                .iter()
+                .map(|s| (*s).to_string())
+                .collect(),
+        };
+        selectors.extend(self.global_extra_remove_selectors.iter().cloned());
+        if let Some(extra) = host_extra {
+            selectors.extend(extra.iter().cloned());
+        }
+        selectors.extend(COOKIE_CONSENT_SELECTORS.iter().map(|s| (*s).to_string()));
+        selectors
+    }
+}
+
+/// Narrows `html` down to the first element matching `selector`'s
+/// re-serialized HTML, for `SiteProfile.main_selector`. Returns `None` if
+/// the selector is invalid or matches nothing, so callers can fall back to
+/// the full document rather than erroring out a fetch over a stale selector.
+pub fn select_main(html: &str, selector: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse(selector).ok()?;
+    document.select(&selector).next().map(|el| el.html())
+}
+
+/// Removes every element matching `level`'s selector set (plus
+/// `COOKIE_CONSENT_SELECTORS`) from `html`, including descendants,
+/// returning the re-serialized document. Ignores any `--site-config`
+/// overrides; see `strip_chrome_with_selectors` for that. Unused within the
+/// bin, which always resolves a selector list via `CleanConfig::resolve`
+/// first; exists for library consumers and is exercised directly by this
+/// module's tests.
+#[allow(dead_code)]
+pub fn strip_chrome(html: &str, level: SanitizeLevel) -> String {
+    let selectors: Vec<String> = selectors_for(level)
+        .iter()
+        .chain(COOKIE_CONSENT_SELECTORS)
+        .map(|s| (*s).to_string())
+        .collect();
+    strip_chrome_with_selectors(html, &selectors)
+}
+
+/// Removes every element matching any of `selectors` from `html`, including
+/// descendants, returning the re-serialized document. `selectors` is
+/// typically built by `CleanConfig::resolve`; invalid selectors are
+/// silently skipped since `CleanConfig`/`SiteConfig::load` already reject
+/// them at startup.
+pub fn strip_chrome_with_selectors(html: &str, selectors: &[String]) -> String {
+    let mut document = Html::parse_document(html);
+
+    let to_remove: Vec<_> = selectors
+        .iter()
+        .filter_map(|s| Selector::parse(s).ok())
+        .flat_map(|selector| {
+            document
+                .select(&selector)
+                .map(|el| el.id())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    for id in to_remove {
+        if let Some(mut node) = document.tree.get_mut(id) {
+            node.detach();
+        }
+    }
+
+    document.html()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_main_narrows_to_matching_element() {
+        let html =
+            r#"<html><body><nav>Nav</nav><main class="content"><p>Body</p></main></body></html>"#;
+        let selected = select_main(html, "main.content").unwrap();
+        assert!(selected.contains("Body"));
+        assert!(!selected.contains("Nav"));
+    }
+
+    #[test]
+    fn test_select_main_returns_none_when_selector_does_not_match() {
+        let html = "<html><body><p>Body</p></body></html>";
+        assert!(select_main(html, "main.content").is_none());
+    }
+
+    #[test]
+    fn test_minimal_strips_only_script_style_noscript() {
+        let html = "<html><body><nav>Nav</nav><script>1</script><p>Body</p></body></html>";
+        let stripped = strip_chrome(html, SanitizeLevel::Minimal);
+        assert!(!stripped.contains("<script>"));
+        assert!(stripped.contains("Nav"));
+        assert!(stripped.contains("Body"));
+    }
+
+    #[test]
+    fn test_standard_strips_nav_and_breadcrumbs() {
+        let html = r#"<html><body><nav>Nav</nav><div class="breadcrumbs">Home &gt; Docs</div><main>Body</main></body></html>"#;
+        let stripped = strip_chrome(html, SanitizeLevel::Standard);
+        assert!(!stripped.contains("Nav"));
+        assert!(!stripped.contains("breadcrumbs"));
+        assert!(stripped.contains("Body"));
+    }
+
+    #[test]
+    fn test_standard_preserves_aside_and_figure() {
+        let html = "<html><body><aside>Related links</aside><figure>Diagram</figure><main>Body</main></body></html>";
+        let stripped = strip_chrome(html, SanitizeLevel::Standard);
+        assert!(stripped.contains("Related links"));
+        assert!(stripped.contains("Diagram"));
+    }
+
+    #[test]
+    fn test_aggressive_strips_aside_figure_and_aria_hidden() {
+        let html = r#"<html><body><aside>Related links</aside><figure>Diagram</figure><span aria-hidden="true">deco</span><main>Body</main></body></html>"#;
+        let stripped = strip_chrome(html, SanitizeLevel::Aggressive);
+        assert!(!stripped.contains("Related links"));
+        assert!(!stripped.contains("Diagram"));
+        assert!(!stripped.contains("deco"));
+        assert!(stripped.contains("Body"));
+    }
+
+    #[test]
+    fn test_minimal_also_strips_cookie_banners() {
+        let html = r#"<html><body>
+            <div id="CybotCookiebotDialog">Cookiebot consent</div>
+            <div id="onetrust-banner-sdk">OneTrust consent</div>
+            <div class="gdpr-notice">We use cookies</div>
+            <p>Body</p>
+            </body></html>"#;
+        let stripped = strip_chrome(html, SanitizeLevel::Minimal);
+        assert!(!stripped.contains("Cookiebot consent"));
+        assert!(!stripped.contains("OneTrust consent"));
+        assert!(!stripped.contains("We use cookies"));
+        assert!(stripped.contains("Body"));
+    }
+
+    #[test]
+    fn test_validate_selector_rejects_invalid_css() {
+        assert!(validate_selector("main.content").is_ok());
+        assert!(validate_selector(":::not-a-selector").is_err());
+    }
+
+    #[test]
+    fn test_clean_config_new_rejects_invalid_global_selector() {
+        assert!(CleanConfig::new(vec![":::bad".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_clean_config_host_extra_removes_selector_the_defaults_miss() {
+        let html = r#"<html><body><nav>Nav</nav><div class="sidebar-xyz">Custom sidebar</div><main>Body</main></body></html>"#;
+
+        // `Minimal`'s defaults only strip script/style/noscript, so this
+        // site's custom sidebar class survives unless added explicitly.
+        assert!(strip_chrome(html, SanitizeLevel::Minimal).contains("Custom sidebar"));
+
+        let config = CleanConfig::default();
+        let selectors = config.resolve(
+            SanitizeLevel::Minimal,
+            Some(&[".sidebar-xyz".to_string()]),
+            None,
+        );
+        let stripped = strip_chrome_with_selectors(html, &selectors);
+        assert!(!stripped.contains("Custom sidebar"));
+        assert!(stripped.contains("Nav"));
+        assert!(stripped.contains("Body"));
+    }
+
+    #[test]
+    fn test_clean_config_host_replace_drops_level_defaults() {
+        let html = r#"<html><body><nav>Nav</nav><div class="sidebar-xyz">Custom sidebar</div><main>Body</main></body></html>"#;
+
+        let config = CleanConfig::default();
+        let selectors = config.resolve(
+            SanitizeLevel::Standard,
+            None,
+            Some(&[".sidebar-xyz".to_string()]),
+        );
+        let stripped = strip_chrome_with_selectors(html, &selectors);
+        assert!(!stripped.contains("Custom sidebar"));
+        // `nav` is part of the `Standard` defaults, which `remove_selectors`
+        // fully replaces rather than extends.
+        assert!(stripped.contains("Nav"));
+        assert!(stripped.contains("Body"));
+    }
+
+    #[test]
+    fn test_clean_config_global_extra_applies_to_every_host() {
+        let html =
+            r#"<html><body><div class="global-promo">Ad</div><main>Body</main></body></html>"#;
+        let config = CleanConfig::new(vec![".global-promo".to_string()]).unwrap();
+        let selectors = config.resolve(SanitizeLevel::Minimal, None, None);
+        let stripped = strip_chrome_with_selectors(html, &selectors);
+        assert!(!stripped.contains("Ad"));
+        assert!(stripped.contains("Body"));
+    }
+
+    /// Pins `strip_chrome`'s output on a chrome-heavy page so a future change
+    /// to selector handling or serialization can't silently alter what
+    /// survives (see `benches/clean_bench.rs` for the same shape used to
+    /// measure performance).
+    #[test]
+    fn test_strip_chrome_standard_snapshot() {
+        let html = r#"<html><head><title>Docs</title></head><body>
+            <nav>Home | Guide | API</nav>
+            <header><h1>Site Header</h1></header>
+            <div class="sidebar breadcrumbs">Home &gt; Guide &gt; Page</div>
+            <main><h2>Section</h2><p>Real article content goes here.</p></main>
+            <aside>Related pages</aside>
+            <footer>Copyright 2026</footer>
+            </body></html>"#;
+        let stripped = strip_chrome(html, SanitizeLevel::Standard);
+        insta::assert_snapshot!(stripped);
+    }
+}
@@ -0,0 +1,355 @@
+//! Per-site fetch defaults loaded from a `--site-config` TOML file.
+//!
+//! ```toml
+//! [clean]
+//! extra_remove_selectors = [".global-promo"]
+//!
+//! [site."docs.python.org"]
+//! prefer = "html"
+//! skip_variations = ["llms-full.txt"]
+//! main_selector = "main.content"
+//! extra_remove_selectors = [".sidebar-xyz"]
+//!
+//! [site."*.rust-lang.org"]
+//! extraction = "readability"
+//! remove_selectors = ["nav", ".promo"]
+//! [site."*.rust-lang.org".headers]
+//! Authorization = "Bearer ..."
+//! ```
+//!
+//! Lookups match the exact host first, then the longest matching glob
+//! pattern (only a `*.` host-suffix wildcard is supported).
+//!
+//! `[clean]` and every `extra_remove_selectors`/`remove_selectors` entry is
+//! validated as CSS at load time (see `sanitize::CleanConfig::resolve`), so
+//! a typo is a startup error rather than a selector that silently matches
+//! nothing on every fetch.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::sanitize::{self, CleanConfig};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SiteProfile {
+    pub skip_variations: Option<Vec<String>>,
+    /// "html" | "llms" — when "llms", the `llms.txt`/`llms-full.txt`
+    /// variations are tried before the page itself and its `.md` guess
+    /// (see `apply_variation_preference`); "html" (or unset) keeps
+    /// `get_url_variations`'s default order, which already tries the page
+    /// itself first
+    pub prefer: Option<String>,
+    /// "readability" | "selectors" — picks the converter for this host when
+    /// `converter`/`FetchInput.converter` aren't set: "selectors" forces the
+    /// raw-HTML converter (meant to pair with `main_selector` pinning down
+    /// the body), "readability" forces the Readability-based converter
+    pub extraction: Option<String>,
+    /// Extra headers sent with every request to this host, including
+    /// frame-recovery and thin-content retry fetches (not the cross-host
+    /// archive.org fallback). Lets a host needing bearer-token auth or a
+    /// custom `Accept` get it without a per-call `FetchInput` field
+    pub headers: Option<HashMap<String, String>>,
+    /// CSS selector for this host's main content element, applied to the
+    /// raw HTML before conversion so a site whose markup confuses
+    /// Readability (or whose chrome survives `RawHtmlConverter`) can have
+    /// its article body pinned down explicitly. Falls back to the full
+    /// document when the selector doesn't match
+    pub main_selector: Option<String>,
+    pub toc_budget: Option<usize>,
+    /// Overrides the server's `--fallback-to-archive` default for this host
+    pub archive_fallback: Option<bool>,
+    /// Overrides the server's `--default-converter` default for this host
+    pub converter: Option<String>,
+    /// Selectors appended to `html_sanitize_level`'s removal set (and
+    /// `[clean].extra_remove_selectors`) for this host only, for chrome a
+    /// site's markup has that the level defaults don't know about
+    pub extra_remove_selectors: Option<Vec<String>>,
+    /// Fully replaces `html_sanitize_level`'s removal set for this host
+    /// (global and cookie-consent selectors still apply). Takes priority
+    /// over `extra_remove_selectors` if both are set
+    pub remove_selectors: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawCleanConfig {
+    #[serde(default)]
+    extra_remove_selectors: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawSiteConfig {
+    #[serde(default)]
+    site: HashMap<String, SiteProfile>,
+    #[serde(default)]
+    clean: RawCleanConfig,
+}
+
+#[derive(Debug, Default)]
+pub struct SiteConfig {
+    profiles: HashMap<String, SiteProfile>,
+    clean: CleanConfig,
+}
+
+impl SiteConfig {
+    /// Parses the TOML source. Errors are intended to abort startup.
+    pub fn parse(toml_source: &str) -> Result<Self, String> {
+        let raw: RawSiteConfig =
+            toml::from_str(toml_source).map_err(|e| format!("invalid site-config TOML: {e}"))?;
+
+        let clean = CleanConfig::new(raw.clean.extra_remove_selectors)
+            .map_err(|e| format!("[clean].extra_remove_selectors: {e}"))?;
+        for (host, profile) in &raw.site {
+            if let Some(prefer) = &profile.prefer
+                && prefer != "html"
+                && prefer != "llms"
+            {
+                return Err(format!(
+                    "[site.\"{host}\"].prefer: must be \"html\" or \"llms\", got {prefer:?}"
+                ));
+            }
+            if let Some(extraction) = &profile.extraction
+                && extraction != "readability"
+                && extraction != "selectors"
+            {
+                return Err(format!(
+                    "[site.\"{host}\"].extraction: must be \"readability\" or \"selectors\", got {extraction:?}"
+                ));
+            }
+            for selector in profile.extra_remove_selectors.iter().flatten() {
+                sanitize::validate_selector(selector)
+                    .map_err(|e| format!("[site.\"{host}\"].extra_remove_selectors: {e}"))?;
+            }
+            for selector in profile.remove_selectors.iter().flatten() {
+                sanitize::validate_selector(selector)
+                    .map_err(|e| format!("[site.\"{host}\"].remove_selectors: {e}"))?;
+            }
+        }
+
+        Ok(Self {
+            profiles: raw.site,
+            clean,
+        })
+    }
+
+    pub async fn load(path: &Path) -> Result<Self, String> {
+        let contents = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| format!("failed to read site-config {}: {e}", path.display()))?;
+        Self::parse(&contents)
+    }
+
+    /// Exact host match wins; otherwise the longest matching `*.suffix` glob wins.
+    pub fn lookup(&self, host: &str) -> Option<&SiteProfile> {
+        if let Some(profile) = self.profiles.get(host) {
+            return Some(profile);
+        }
+        self.profiles
+            .iter()
+            .filter(|(pattern, _)| glob_match(pattern, host))
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(_, profile)| profile)
+    }
+
+    /// The `[clean]` table's global removal-selector additions, applied to
+    /// every host regardless of `lookup`'s per-host result.
+    pub fn clean_config(&self) -> &CleanConfig {
+        &self.clean
+    }
+}
+
+fn glob_match(pattern: &str, host: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => host.len() > suffix.len() && host.ends_with(suffix),
+        None => pattern == host,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_match_wins_over_glob() {
+        let config = SiteConfig::parse(
+            r#"
+            [site."docs.rust-lang.org"]
+            skip_variations = ["exact"]
+            [site."*.rust-lang.org"]
+            skip_variations = ["glob"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.lookup("docs.rust-lang.org").unwrap().skip_variations,
+            Some(vec!["exact".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_longest_glob_wins() {
+        let config = SiteConfig::parse(
+            r#"
+            [site."*.rust-lang.org"]
+            skip_variations = ["short"]
+            [site."*.docs.rust-lang.org"]
+            skip_variations = ["long"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config
+                .lookup("api.docs.rust-lang.org")
+                .unwrap()
+                .skip_variations,
+            Some(vec!["long".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_no_match_returns_none() {
+        let config = SiteConfig::parse(r#"[site."example.com"]"#).unwrap();
+        assert!(config.lookup("other.com").is_none());
+    }
+
+    #[test]
+    fn test_glob_requires_nonempty_prefix_match() {
+        assert!(!glob_match("*.rust-lang.org", "rust-lang.org"));
+        assert!(glob_match("*.rust-lang.org", "docs.rust-lang.org"));
+    }
+
+    #[test]
+    fn test_invalid_toml_is_an_error() {
+        let result = SiteConfig::parse("not valid toml {{{");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_main_selector_applies_only_to_matching_host() {
+        let config = SiteConfig::parse(
+            r#"
+            [site."docs.example.com"]
+            main_selector = "main.content"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.lookup("docs.example.com").unwrap().main_selector,
+            Some("main.content".to_string())
+        );
+        assert!(config.lookup("other.example.com").is_none());
+    }
+
+    #[test]
+    fn test_full_profile_fields_parse() {
+        let config = SiteConfig::parse(
+            r#"
+            [site."docs.python.org"]
+            skip_variations = ["llms-full.txt"]
+            prefer = "html"
+            extraction = "readability"
+            toc_budget = 2000
+            main_selector = "main#content"
+            [site."docs.python.org".headers]
+            "X-Test" = "1"
+            "#,
+        )
+        .unwrap();
+
+        let profile = config.lookup("docs.python.org").unwrap();
+        assert_eq!(
+            profile.skip_variations,
+            Some(vec!["llms-full.txt".to_string()])
+        );
+        assert_eq!(profile.prefer, Some("html".to_string()));
+        assert_eq!(profile.extraction, Some("readability".to_string()));
+        assert_eq!(profile.toc_budget, Some(2000));
+        assert_eq!(profile.main_selector, Some("main#content".to_string()));
+        assert_eq!(
+            profile.headers.as_ref().unwrap().get("X-Test").unwrap(),
+            "1"
+        );
+    }
+
+    #[test]
+    fn test_invalid_prefer_value_is_a_load_error() {
+        let result = SiteConfig::parse(
+            r#"
+            [site."docs.example.com"]
+            prefer = "pdf"
+            "#,
+        );
+        let err = result.unwrap_err();
+        assert!(err.contains("[site.\"docs.example.com\"].prefer"), "{err}");
+    }
+
+    #[test]
+    fn test_invalid_extraction_value_is_a_load_error() {
+        let result = SiteConfig::parse(
+            r#"
+            [site."docs.example.com"]
+            extraction = "ocr"
+            "#,
+        );
+        let err = result.unwrap_err();
+        assert!(
+            err.contains("[site.\"docs.example.com\"].extraction"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_invalid_global_clean_selector_is_a_load_error() {
+        let result = SiteConfig::parse(
+            r#"
+            [clean]
+            extra_remove_selectors = [":::not-a-selector"]
+            "#,
+        );
+        let err = result.unwrap_err();
+        assert!(err.contains("[clean].extra_remove_selectors"), "{err}");
+    }
+
+    #[test]
+    fn test_invalid_host_remove_selector_is_a_load_error() {
+        let result = SiteConfig::parse(
+            r#"
+            [site."docs.example.com"]
+            remove_selectors = [":::not-a-selector"]
+            "#,
+        );
+        let err = result.unwrap_err();
+        assert!(
+            err.contains("[site.\"docs.example.com\"].remove_selectors"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_clean_config_merges_global_and_per_host_extra_selectors() {
+        let config = SiteConfig::parse(
+            r#"
+            [clean]
+            extra_remove_selectors = [".global-promo"]
+
+            [site."docs.example.com"]
+            extra_remove_selectors = [".sidebar-xyz"]
+            "#,
+        )
+        .unwrap();
+
+        let profile = config.lookup("docs.example.com").unwrap();
+        let resolved = config.clean_config().resolve(
+            crate::sanitize::SanitizeLevel::Standard,
+            profile.extra_remove_selectors.as_deref(),
+            profile.remove_selectors.as_deref(),
+        );
+        assert!(resolved.iter().any(|s| s == ".global-promo"));
+        assert!(resolved.iter().any(|s| s == ".sidebar-xyz"));
+        assert!(resolved.iter().any(|s| s == "nav"));
+    }
+}
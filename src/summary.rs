@@ -0,0 +1,73 @@
+//! Extracts a short summary for `FetchOutput.summary`, so the caller can see
+//! what a fetch actually got without reading the cached file.
+
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+
+/// Summaries longer than this are truncated (on a char boundary) with a
+/// trailing ellipsis.
+pub const MAX_SUMMARY_LEN: usize = 200;
+
+/// Returns the text of the first `Tag::Paragraph` in `markdown` (headings
+/// are a different tag, so a page opening with just a title falls through
+/// to its first real paragraph), truncated to `MAX_SUMMARY_LEN` characters.
+/// Returns `None` if the document has no paragraph with text.
+pub fn extract_summary(markdown: &str) -> Option<String> {
+    let mut in_paragraph = false;
+    let mut text = String::new();
+
+    for event in Parser::new_ext(markdown, Options::all()) {
+        match event {
+            Event::Start(Tag::Paragraph) => in_paragraph = true,
+            Event::End(TagEnd::Paragraph) => break,
+            Event::Text(t) | Event::Code(t) if in_paragraph => text.push_str(&t),
+            _ => {}
+        }
+    }
+
+    let text = text.trim();
+    (!text.is_empty()).then(|| truncate_with_ellipsis(text, MAX_SUMMARY_LEN))
+}
+
+fn truncate_with_ellipsis(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        return s.to_string();
+    }
+    let truncated: String = s.chars().take(max_chars).collect();
+    format!("{truncated}...")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_first_paragraph() {
+        let markdown = "# React Documentation\n\nThis is the React documentation introduction page.\n\nMore text.";
+        assert_eq!(
+            extract_summary(markdown),
+            Some("This is the React documentation introduction page.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_skips_leading_heading() {
+        let markdown = "# Title\n\nThe real first paragraph.";
+        assert_eq!(
+            extract_summary(markdown),
+            Some("The real first paragraph.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_truncates_long_paragraph() {
+        let markdown = format!("{} more text", "word ".repeat(100));
+        let result = extract_summary(&markdown).unwrap();
+        assert!(result.ends_with("..."));
+        assert_eq!(result.chars().count(), MAX_SUMMARY_LEN + 3);
+    }
+
+    #[test]
+    fn test_no_paragraph_returns_none() {
+        assert_eq!(extract_summary("# Just a heading"), None);
+    }
+}
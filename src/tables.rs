@@ -0,0 +1,187 @@
+//! Selective table conversion for `FetchInput.preserve_tables`.
+//!
+//! Simple tables (no `colspan`/`rowspan`, single header row) convert cleanly
+//! to GFM pipe tables. Complex tables (merged cells, multi-column headers,
+//! nested tables) do not have a lossless GFM representation, so they are
+//! left as literal `<table>...</table>` HTML, which most Markdown renderers
+//! pass through untouched.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+
+static TABLE_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<table\b[^>]*>|</table\s*>").unwrap());
+
+/// Byte ranges of top-level `<table>...</table>` blocks in `html`, found by
+/// tracking open/close tag depth rather than relying on a parsed tree (whose
+/// serialized form may not match the original source byte-for-byte, e.g.
+/// html5ever inserts an implicit `<tbody>`).
+fn find_table_spans(html: &str) -> Vec<std::ops::Range<usize>> {
+    let mut spans = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0usize;
+
+    for m in TABLE_TAG.find_iter(html) {
+        if m.as_str().starts_with("</") {
+            depth = depth.saturating_sub(1);
+            if depth == 0 {
+                spans.push(start..m.end());
+            }
+        } else {
+            if depth == 0 {
+                start = m.start();
+            }
+            depth += 1;
+        }
+    }
+
+    spans
+}
+
+/// Returns `true` if `element` (a `<table>`) has no `colspan`/`rowspan`
+/// attributes and no nested `<table>`, meaning it converts losslessly to GFM.
+pub fn is_simple_table(element: &ElementRef) -> bool {
+    let cell_selector = Selector::parse("th, td").unwrap();
+    let nested_table_selector = Selector::parse("table").unwrap();
+
+    if element.select(&nested_table_selector).next().is_some() {
+        return false;
+    }
+
+    element.select(&cell_selector).all(|cell| {
+        cell.value().attr("colspan").is_none() && cell.value().attr("rowspan").is_none()
+    })
+}
+
+fn cell_text(cell: &ElementRef) -> String {
+    cell.text().collect::<String>().trim().replace('|', r"\|")
+}
+
+/// Converts a simple `<table>` element to a GFM pipe table.
+fn render_gfm_table(element: &ElementRef) -> Option<String> {
+    let row_selector = Selector::parse("tr").unwrap();
+    let cell_selector = Selector::parse("th, td").unwrap();
+
+    let rows: Vec<Vec<String>> = element
+        .select(&row_selector)
+        .map(|row| row.select(&cell_selector).map(|c| cell_text(&c)).collect())
+        .filter(|row: &Vec<String>| !row.is_empty())
+        .collect();
+
+    let (header, body) = rows.split_first()?;
+    let column_count = header.len();
+
+    let mut out = String::new();
+    out.push_str("| ");
+    out.push_str(&header.join(" | "));
+    out.push_str(" |\n|");
+    out.push_str(&" --- |".repeat(column_count));
+    out.push('\n');
+    for row in body {
+        out.push_str("| ");
+        out.push_str(&row.join(" | "));
+        out.push_str(" |\n");
+    }
+
+    Some(out)
+}
+
+/// Replaces every `<table>` in `html` with either a GFM pipe table (simple
+/// tables) or the original `<table>...</table>` HTML literal (complex
+/// tables), so that `html_to_markdown` never has to convert tables itself.
+pub fn preprocess_tables(html: &str) -> String {
+    let table_selector = Selector::parse("table").unwrap();
+    let spans = find_table_spans(html);
+
+    let mut result = String::with_capacity(html.len());
+    let mut last_end = 0;
+
+    for span in spans {
+        let original = &html[span.clone()];
+        let document = Html::parse_fragment(original);
+        let replacement = document
+            .select(&table_selector)
+            .next()
+            .filter(is_simple_table)
+            .and_then(|table| render_gfm_table(&table));
+
+        result.push_str(&html[last_end..span.start]);
+        result.push_str(replacement.as_deref().unwrap_or(original));
+        last_end = span.end;
+    }
+    result.push_str(&html[last_end..]);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first_table(html: &str) -> Html {
+        Html::parse_fragment(html)
+    }
+
+    #[test]
+    fn test_simple_table_is_simple() {
+        let doc = first_table("<table><tr><th>A</th></tr><tr><td>1</td></tr></table>");
+        let table = doc
+            .select(&Selector::parse("table").unwrap())
+            .next()
+            .unwrap();
+        assert!(is_simple_table(&table));
+    }
+
+    #[test]
+    fn test_colspan_table_is_not_simple() {
+        let doc = first_table(
+            "<table><tr><th colspan=\"2\">A</th></tr><tr><td>1</td><td>2</td></tr></table>",
+        );
+        let table = doc
+            .select(&Selector::parse("table").unwrap())
+            .next()
+            .unwrap();
+        assert!(!is_simple_table(&table));
+    }
+
+    #[test]
+    fn test_nested_table_is_not_simple() {
+        let doc =
+            first_table("<table><tr><td><table><tr><td>x</td></tr></table></td></tr></table>");
+        let table = doc
+            .select(&Selector::parse("table").unwrap())
+            .next()
+            .unwrap();
+        assert!(!is_simple_table(&table));
+    }
+
+    #[test]
+    fn test_render_gfm_table() {
+        let doc = first_table(
+            "<table><tr><th>A</th><th>B</th></tr><tr><td>1</td><td>2</td></tr></table>",
+        );
+        let table = doc
+            .select(&Selector::parse("table").unwrap())
+            .next()
+            .unwrap();
+        let rendered = render_gfm_table(&table).unwrap();
+        assert_eq!(rendered, "| A | B |\n| --- | --- |\n| 1 | 2 |\n");
+    }
+
+    #[test]
+    fn test_preprocess_tables_replaces_simple_table() {
+        let html = "<p>intro</p><table><tr><th>A</th></tr><tr><td>1</td></tr></table>";
+        let processed = preprocess_tables(html);
+        assert!(processed.contains("| A |"));
+        assert!(!processed.contains("<table>"));
+    }
+
+    #[test]
+    fn test_preprocess_tables_keeps_complex_table_as_html() {
+        let html = "<table><tr><th colspan=\"2\">A</th></tr></table>";
+        let processed = preprocess_tables(html);
+        assert!(processed.contains("<table"));
+    }
+}
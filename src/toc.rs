@@ -7,13 +7,35 @@ use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
 
 pub const DEFAULT_TOC_BUDGET: usize = 4000;
 pub const DEFAULT_TOC_THRESHOLD: usize = 8000;
+pub const DEFAULT_TOC_SEPARATOR: &str = "→";
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TocConfig {
     /// Maximum `ToC` size in bytes. Algorithm selects deepest heading level that fits.
     pub toc_budget: usize,
     /// Minimum document size to generate `ToC`. Smaller docs return `None`.
     pub full_content_threshold: usize,
+    /// Strip inline HTML tags (e.g. `<span>`, `<code>`, `<b>`) from heading
+    /// text instead of preserving them as written. Defaults to `false`
+    /// (preserve source).
+    pub strip_inline_html: bool,
+    /// Separator between line number and heading text. Must be non-empty.
+    /// Defaults to `"→"`.
+    pub separator: String,
+    /// Caps the deepest heading level `find_optimal_level` will try, for
+    /// callers who want direct control over `ToC` granularity independent of
+    /// `toc_budget` (see `FetchInput.max_heading_depth`). `None` (the
+    /// default here) leaves level selection purely budget-driven, up to
+    /// whatever level the document actually uses.
+    pub max_heading_depth: Option<u8>,
+    /// Indent each heading by two spaces per level below the shallowest
+    /// included level, so nested headings are visually distinguishable from
+    /// a flat line→text list (see `FetchInput.toc_indent`). Defaults to `false`.
+    pub indent: bool,
+    /// Prefix each heading with a hierarchical number (`1`, `1.1`, `1.2`,
+    /// `2`, ...) computed from the rendered heading structure (see
+    /// `FetchInput.toc_numbering`). Defaults to `false`.
+    pub numbering: bool,
 }
 
 impl Default for TocConfig {
@@ -21,6 +43,11 @@ impl Default for TocConfig {
         Self {
             toc_budget: DEFAULT_TOC_BUDGET,
             full_content_threshold: DEFAULT_TOC_THRESHOLD,
+            strip_inline_html: false,
+            separator: DEFAULT_TOC_SEPARATOR.to_string(),
+            max_heading_depth: None,
+            indent: false,
+            numbering: false,
         }
     }
 }
@@ -34,6 +61,8 @@ pub struct Heading {
     pub level: u8,
     /// Line number where heading appears (1-indexed)
     pub line_number: usize,
+    /// Byte offset where the heading's markdown starts
+    pub start_byte: usize,
     /// Heading text with formatting preserved
     pub text: String,
 }
@@ -54,9 +83,12 @@ fn is_empty_or_invisible(text: &str) -> bool {
     })
 }
 
-/// Extracts headings with line numbers, filtering out empty anchor links.
+/// Extracts headings with line numbers, filtering out empty anchor links and,
+/// when `strip_inline_html` is set, inline HTML tags (e.g. `<span>`, `<code>`,
+/// `<b>`) embedded in the heading. `pub` (rather than `pub(crate)`) so
+/// `benches/toc_bench.rs` can measure this phase of `generate_toc` on its own.
 #[allow(clippy::too_many_lines)]
-fn extract_headings(markdown: &str) -> Vec<Heading> {
+pub fn extract_headings(markdown: &str, strip_inline_html: bool) -> Vec<Heading> {
     use std::ops::Range;
 
     struct HeadingState {
@@ -64,6 +96,7 @@ fn extract_headings(markdown: &str) -> Vec<Heading> {
         start: usize,
         line_number: usize,
         empty_link_ranges: Vec<Range<usize>>,
+        html_ranges: Vec<Range<usize>>,
         current_link: Option<LinkState>,
     }
 
@@ -96,9 +129,15 @@ fn extract_headings(markdown: &str) -> Vec<Heading> {
                     start: range.start,
                     line_number: current_line,
                     empty_link_ranges: Vec::new(),
+                    html_ranges: Vec::new(),
                     current_link: None,
                 });
             }
+            Event::Html(_) | Event::InlineHtml(_) => {
+                if strip_inline_html && let Some(heading) = &mut current_heading {
+                    heading.html_ranges.push(range.clone());
+                }
+            }
             Event::Start(Tag::Link { .. }) => {
                 if let Some(heading) = &mut current_heading {
                     heading.current_link = Some(LinkState {
@@ -133,11 +172,16 @@ fn extract_headings(markdown: &str) -> Vec<Heading> {
                     // Extract full heading text
                     let full_text = markdown.get(heading.start..range.end).unwrap_or("");
 
-                    // Build text excluding empty link ranges (convert absolute→relative offsets)
+                    // Build text excluding empty link ranges and (if requested)
+                    // inline HTML ranges (convert absolute→relative offsets)
                     let mut text = String::new();
                     let mut last_end = 0;
 
-                    for empty_range in &heading.empty_link_ranges {
+                    let mut excluded_ranges = heading.empty_link_ranges;
+                    excluded_ranges.extend(heading.html_ranges);
+                    excluded_ranges.sort_by_key(|r| r.start);
+
+                    for empty_range in &excluded_ranges {
                         let relative_start = empty_range.start.saturating_sub(heading.start);
                         let relative_end = empty_range.end.saturating_sub(heading.start);
 
@@ -207,7 +251,8 @@ fn extract_headings(markdown: &str) -> Vec<Heading> {
                         headings.push(Heading {
                             level: level_num,
                             line_number: heading.line_number,
-                            text: text.to_string(),
+                            start_byte: heading.start,
+                            text,
                         });
                     }
                 }
@@ -219,32 +264,203 @@ fn extract_headings(markdown: &str) -> Vec<Heading> {
     headings
 }
 
+/// Incrementally updates `old_headings` (previously extracted from
+/// `old_markdown` with the same `strip_inline_html` setting) for
+/// `new_markdown`, re-parsing only the suffix after the byte where the two
+/// documents first diverge - the common case when a cached document is
+/// refreshed after an append-only or otherwise prefix-unchanged edit.
+/// Falls back to a full `extract_headings` call when the very first line
+/// differs. Always produces the same result as calling `extract_headings`
+/// on `new_markdown` directly, just without re-parsing the unchanged part.
+///
+/// Needs `old_markdown`'s full text, not just its length: a rolling hash of
+/// the shared prefix can say *whether* a change happened, but finding
+/// *where* still requires comparing the actual bytes.
+///
+/// Unused within the bin, which never caches a document's previous
+/// `Vec<Heading>` anywhere (`refresh_cache` rewrites cached files without
+/// generating a `ToC` at all; `fetch`/`reconvert` regenerate one from
+/// scratch, statelessly, on every call); exists for library consumers that
+/// keep their own heading-list cache alongside the document text.
+#[allow(dead_code)]
+pub fn extract_headings_incremental(
+    old_headings: &[Heading],
+    old_markdown: &str,
+    new_markdown: &str,
+    strip_inline_html: bool,
+) -> Vec<Heading> {
+    let shared_prefix_len = old_markdown
+        .as_bytes()
+        .iter()
+        .zip(new_markdown.as_bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    // Snap back to the start of the line the first difference falls on, so
+    // a heading only partially inside the shared prefix is treated as
+    // changed (and re-parsed) rather than kept with stale text.
+    let boundary = new_markdown[..shared_prefix_len]
+        .rfind('\n')
+        .map_or(0, |pos| pos + 1);
+
+    if boundary == 0 {
+        return extract_headings(new_markdown, strip_inline_html);
+    }
+
+    let boundary_line = new_markdown[..boundary].matches('\n').count() + 1;
+
+    let mut headings: Vec<Heading> = old_headings
+        .iter()
+        .filter(|h| h.start_byte < boundary)
+        .cloned()
+        .collect();
+
+    let suffix_headings = extract_headings(&new_markdown[boundary..], strip_inline_html);
+    headings.extend(suffix_headings.into_iter().map(|h| Heading {
+        start_byte: h.start_byte + boundary,
+        line_number: h.line_number + boundary_line - 1,
+        ..h
+    }));
+
+    headings
+}
+
+/// Line-number column width `render_toc` pads to, derived from the largest
+/// line number that will appear in the rendered `ToC`.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn line_number_width(max_line_num: usize) -> usize {
+    if max_line_num < 100 {
+        3
+    } else if max_line_num < 1000 {
+        4
+    } else if max_line_num < 10000 {
+        5
+    } else {
+        ((max_line_num as f64).log10().floor() as usize + 1).max(3)
+    }
+}
+
 /// Returns deepest heading level that fits within budget, with rendered `ToC`.
-fn find_optimal_level(headings: &[Heading], budget: usize) -> Option<(u8, String)> {
+///
+/// Each level's rendered byte size is computed arithmetically from
+/// per-level cumulative totals (count, text bytes, max line number) rather
+/// than by rendering and measuring the string, since on large documents
+/// `render_toc` dominates the cost when called once per level. Size still
+/// isn't monotonic in level - a wider line-number column at a deeper level
+/// can make the render *larger* than a shallower one - so every level is
+/// checked and the deepest that fits wins; only the chosen level is
+/// actually rendered.
+///
+/// `indent` and `numbering` add a per-heading, hierarchy-dependent prefix
+/// that the arithmetic totals above can't account for cheaply, so when
+/// either is enabled each candidate level is rendered and measured instead.
+///
+/// `pub` (rather than `pub(crate)`) so `benches/toc_bench.rs` can measure
+/// this phase of `generate_toc` on its own, given pre-extracted headings.
+pub fn find_optimal_level(
+    headings: &[Heading],
+    budget: usize,
+    separator: &str,
+    max_level_cap: Option<u8>,
+    indent: bool,
+    numbering: bool,
+) -> Option<(u8, String)> {
     if headings.is_empty() {
         return None;
     }
 
     let max_level = headings.iter().map(|h| h.level).max().unwrap_or(1);
+    let max_level = max_level_cap.map_or(max_level, |cap| max_level.min(cap));
+
+    if indent || numbering {
+        let mut best: Option<(u8, String)> = None;
+        for level in 1..=max_level {
+            let rendered = render_toc(headings, level, separator, indent, numbering);
+            if !rendered.is_empty() && rendered.len() <= budget {
+                best = Some((level, rendered));
+            }
+        }
+        return best;
+    }
 
-    let mut best: Option<(u8, String)> = None;
+    let sep_len = separator.len();
+
+    let mut level_count = [0usize; 7];
+    let mut level_text_bytes = [0usize; 7];
+    let mut level_max_line = [0usize; 7];
+    for h in headings {
+        let l = h.level as usize;
+        level_count[l] += 1;
+        level_text_bytes[l] += h.text.len();
+        level_max_line[l] = level_max_line[l].max(h.line_number);
+    }
+
+    let mut best_level = None;
+    let (mut count, mut text_bytes, mut max_line) = (0usize, 0usize, 0usize);
     for level in 1..=max_level {
-        let rendered = render_toc(headings, level);
-        if rendered.is_empty() {
+        let l = level as usize;
+        count += level_count[l];
+        text_bytes += level_text_bytes[l];
+        max_line = max_line.max(level_max_line[l]);
+
+        if count == 0 {
             continue; // Skip levels with no headings
         }
 
-        let byte_size = rendered.len();
+        let width = line_number_width(max_line);
+        let byte_size = text_bytes + count * (width + sep_len) + (count - 1);
         if byte_size <= budget {
-            best = Some((level, rendered));
+            best_level = Some(level);
         }
         // Don't break early - size may not increase monotonically
     }
 
-    best
+    best_level.map(|level| (level, render_toc(headings, level, separator, false, false)))
+}
+
+/// Computes each filtered heading's hierarchical number (`1`, `1.1`, `2`,
+/// ...) from the already level-filtered sequence, so numbers restart
+/// correctly when intermediate levels are excluded by `max_level` rather
+/// than reflecting gaps from the document's raw heading levels.
+fn number_headings(filtered: &[&Heading]) -> Vec<String> {
+    let mut stack: Vec<(u8, usize)> = Vec::new();
+    let mut numbers = Vec::with_capacity(filtered.len());
+
+    for h in filtered {
+        while stack.last().is_some_and(|&(level, _)| level > h.level) {
+            stack.pop();
+        }
+        if let Some(last) = stack.last_mut().filter(|(level, _)| *level == h.level) {
+            last.1 += 1;
+        } else {
+            stack.push((h.level, 1));
+        }
+
+        let number = stack
+            .iter()
+            .map(|(_, n)| n.to_string())
+            .collect::<Vec<_>>()
+            .join(".");
+        numbers.push(number);
+    }
+
+    numbers
 }
 
-fn render_toc(headings: &[Heading], max_level: u8) -> String {
+/// Renders the `{line_number}{separator}{heading_text}` lines for every
+/// heading at or above `max_level`. `pub` (rather than `pub(crate)`) so
+/// `benches/toc_bench.rs` can measure this phase of `generate_toc` on its own.
+pub fn render_toc(
+    headings: &[Heading],
+    max_level: u8,
+    separator: &str,
+    indent: bool,
+    numbering: bool,
+) -> String {
     use std::fmt::Write;
 
     let filtered: Vec<_> = headings.iter().filter(|h| h.level <= max_level).collect();
@@ -253,23 +469,10 @@ fn render_toc(headings: &[Heading], max_level: u8) -> String {
         return String::new();
     }
 
-    debug_assert!(!filtered.is_empty());
     let max_line_num = filtered.last().unwrap().line_number;
-
-    #[allow(
-        clippy::cast_precision_loss,
-        clippy::cast_possible_truncation,
-        clippy::cast_sign_loss
-    )]
-    let width = if max_line_num < 100 {
-        3
-    } else if max_line_num < 1000 {
-        4
-    } else if max_line_num < 10000 {
-        5
-    } else {
-        ((max_line_num as f64).log10().floor() as usize + 1).max(3)
-    };
+    let width = line_number_width(max_line_num);
+    let min_level = filtered.iter().map(|h| h.level).min().unwrap_or(1);
+    let numbers = numbering.then(|| number_headings(&filtered));
 
     // Pre-allocate to reduce reallocations
     let estimated_size = filtered.len() * (width + 34);
@@ -279,7 +482,16 @@ fn render_toc(headings: &[Heading], max_level: u8) -> String {
         if i > 0 {
             result.push('\n');
         }
-        write!(result, "{:>width$}→{}", h.line_number, h.text).unwrap();
+        write!(result, "{:>width$}{separator}", h.line_number).unwrap();
+        if indent {
+            for _ in 0..(h.level - min_level) {
+                result.push_str("  ");
+            }
+        }
+        if let Some(numbers) = &numbers {
+            write!(result, "{} ", numbers[i]).unwrap();
+        }
+        result.push_str(&h.text);
     }
 
     result
@@ -292,16 +504,83 @@ pub fn generate_toc(markdown: &str, total_bytes: usize, config: &TocConfig) -> O
         return None;
     }
 
-    let headings = extract_headings(markdown);
+    let headings = extract_headings(markdown, config.strip_inline_html);
     if headings.is_empty() {
         return None;
     }
 
-    let (_level, toc) = find_optimal_level(&headings, config.toc_budget)?;
+    debug_assert!(!config.separator.is_empty(), "separator must be non-empty");
+    let (_level, toc) = find_optimal_level(
+        &headings,
+        config.toc_budget,
+        &config.separator,
+        config.max_heading_depth,
+        config.indent,
+        config.numbering,
+    )?;
 
     if toc.is_empty() { None } else { Some(toc) }
 }
 
+/// A document section bounded by one heading and the next heading at or
+/// above the same level (or the end of the document).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionBoundary {
+    pub heading: Heading,
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+/// Splits `markdown` into sections at every heading whose level is `level`
+/// or shallower (1 = H1 only, 6 = every heading). Each section spans from
+/// its heading's start byte to the next qualifying heading's start byte, or
+/// to the end of the document for the last section. Building block for
+/// chunking strategies that need to split a document without cutting
+/// mid-paragraph (see `FetchInput.chunk_by_heading`).
+pub fn find_section_boundaries(markdown: &str, level: u8) -> Vec<SectionBoundary> {
+    let headings: Vec<Heading> = extract_headings(markdown, false)
+        .into_iter()
+        .filter(|h| h.level <= level)
+        .collect();
+
+    let mut boundaries = Vec::with_capacity(headings.len());
+    for i in 0..headings.len() {
+        let start_byte = headings[i].start_byte;
+        let end_byte = headings
+            .get(i + 1)
+            .map_or(markdown.len(), |next| next.start_byte);
+        boundaries.push(SectionBoundary {
+            heading: headings[i].clone(),
+            start_byte,
+            end_byte,
+        });
+    }
+
+    boundaries
+}
+
+/// Converts heading text to a GitHub-style slug: lowercased, with runs of
+/// non-alphanumeric characters (including the heading's leading `#`s)
+/// collapsed to a single hyphen, for naming the per-section files
+/// `FetchInput.chunk_by_heading` produces.
+pub fn github_style_slug(heading_text: &str) -> String {
+    let mut slug = String::with_capacity(heading_text.len());
+    let mut last_was_hyphen = true; // swallow a leading hyphen
+    for c in heading_text.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+            last_was_hyphen = false;
+        } else if !last_was_hyphen {
+            slug.push('-');
+            last_was_hyphen = true;
+        }
+    }
+    if slug.ends_with('-') {
+        slug.pop();
+    }
+    slug
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -313,7 +592,7 @@ mod tests {
     #[test]
     fn test_extract_simple_headings() {
         let md = "# H1\n## H2\n### H3";
-        let headings = extract_headings(md);
+        let headings = extract_headings(md, false);
         assert_eq!(headings.len(), 3);
         assert_eq!(headings[0].level, 1);
         assert_eq!(headings[0].line_number, 1);
@@ -322,10 +601,42 @@ mod tests {
         assert_eq!(headings[1].text, "## H2");
     }
 
+    #[test]
+    fn test_extract_headings_preserves_inline_html_by_default() {
+        let md = "## <span>Title</span>";
+        let headings = extract_headings(md, false);
+        assert_eq!(headings.len(), 1);
+        assert_eq!(headings[0].text, "## <span>Title</span>");
+    }
+
+    #[test]
+    fn test_extract_headings_strips_span_tag_when_requested() {
+        let md = "## <span>Title</span>";
+        let headings = extract_headings(md, true);
+        assert_eq!(headings.len(), 1);
+        assert_eq!(headings[0].text, "## Title");
+    }
+
+    #[test]
+    fn test_extract_headings_strips_code_tag_when_requested() {
+        let md = "## Using <code>fetch()</code>";
+        let headings = extract_headings(md, true);
+        assert_eq!(headings.len(), 1);
+        assert_eq!(headings[0].text, "## Using fetch()");
+    }
+
+    #[test]
+    fn test_extract_headings_strips_bold_tag_when_requested() {
+        let md = "## <b>Important</b> Notice";
+        let headings = extract_headings(md, true);
+        assert_eq!(headings.len(), 1);
+        assert_eq!(headings[0].text, "## Important Notice");
+    }
+
     #[test]
     fn test_ignore_fenced_code_blocks() {
         let md = "# Real\n```\n# Fake\n```\n## Also Real";
-        let headings = extract_headings(md);
+        let headings = extract_headings(md, false);
         assert_eq!(headings.len(), 2);
         assert_eq!(headings[0].text, "# Real");
         assert_eq!(headings[1].text, "## Also Real");
@@ -334,7 +645,7 @@ mod tests {
     #[test]
     fn test_ignore_indented_code_blocks() {
         let md = "# Real\n\n    # Not a heading (indented)\n\n## Real2";
-        let headings = extract_headings(md);
+        let headings = extract_headings(md, false);
         assert_eq!(headings.len(), 2);
         assert_eq!(headings[0].text, "# Real");
         assert_eq!(headings[1].text, "## Real2");
@@ -343,7 +654,7 @@ mod tests {
     #[test]
     fn test_setext_headings() {
         let md = "H1\n==\n\nH2\n--";
-        let headings = extract_headings(md);
+        let headings = extract_headings(md, false);
         assert_eq!(headings.len(), 2);
         assert_eq!(headings[0].level, 1);
         assert_eq!(headings[1].level, 2);
@@ -353,56 +664,56 @@ mod tests {
     fn test_empty_links_excluded() {
         // Empty anchor links should be excluded
         let md = "## Writing markup with JSX [](#writing-markup-with-jsx)";
-        let headings = extract_headings(md);
+        let headings = extract_headings(md, false);
         assert_eq!(headings.len(), 1);
         assert_eq!(headings[0].text, "## Writing markup with JSX");
 
         // Multiple empty links - all excluded
         let md2 = "### Title [](#anchor1) [](#anchor2)";
-        let headings2 = extract_headings(md2);
+        let headings2 = extract_headings(md2, false);
         assert_eq!(headings2.len(), 1);
         assert_eq!(headings2[0].text, "### Title");
 
         // No link - full text preserved
         let md3 = "# Simple Heading";
-        let headings3 = extract_headings(md3);
+        let headings3 = extract_headings(md3, false);
         assert_eq!(headings3.len(), 1);
         assert_eq!(headings3[0].text, "# Simple Heading");
 
         // Link with text - KEPT (not excluded)
         let md4 = "## Title [link](url) more text";
-        let headings4 = extract_headings(md4);
+        let headings4 = extract_headings(md4, false);
         assert_eq!(headings4.len(), 1);
         assert_eq!(headings4[0].text, "## Title [link](url) more text");
 
         // Mix of empty and non-empty links
         let md5 = "## Check [docs](url) for details [](#anchor)";
-        let headings5 = extract_headings(md5);
+        let headings5 = extract_headings(md5, false);
         assert_eq!(headings5.len(), 1);
         assert_eq!(headings5[0].text, "## Check [docs](url) for details");
 
         // Whitespace collapsing: empty link removal should not leave double spaces
         let md6 = "## [¶](#anchor) Title with text";
-        let headings6 = extract_headings(md6);
+        let headings6 = extract_headings(md6, false);
         assert_eq!(headings6.len(), 1);
         assert_eq!(headings6[0].text, "## Title with text");
         assert!(!headings6[0].text.contains("  ")); // No double spaces
 
         // Heading with only empty links should be filtered out
         let md7 = "## [](#anchor) [¶](#another)";
-        let headings7 = extract_headings(md7);
+        let headings7 = extract_headings(md7, false);
         assert_eq!(headings7.len(), 0); // Filtered out entirely
 
         // Heading with only hashes and empty link should be filtered
-        let md8 = "### [​](#anchor)";
-        let headings8 = extract_headings(md8);
+        let md8 = "### [\u{200B}](#anchor)";
+        let headings8 = extract_headings(md8, false);
         assert_eq!(headings8.len(), 0);
     }
 
     #[test]
     fn test_unicode_headings() {
         let md = "# 你好世界\n## 🎉 Emoji Heading";
-        let headings = extract_headings(md);
+        let headings = extract_headings(md, false);
         assert_eq!(headings.len(), 2);
         assert!(headings[0].text.contains("你好世界"));
         assert!(headings[1].text.contains("🎉"));
@@ -412,7 +723,7 @@ mod tests {
     fn test_crlf_line_endings() {
         // Windows-style CRLF line endings should be counted correctly
         let md = "# First\r\n## Second\r\n### Third";
-        let headings = extract_headings(md);
+        let headings = extract_headings(md, false);
         assert_eq!(headings.len(), 3);
         assert_eq!(headings[0].line_number, 1);
         assert_eq!(headings[1].line_number, 2);
@@ -426,7 +737,7 @@ mod tests {
     fn test_mixed_line_endings() {
         // Mix of LF and CRLF should still count correctly
         let md = "# First\n## Second\r\n### Third\n#### Fourth";
-        let headings = extract_headings(md);
+        let headings = extract_headings(md, false);
         assert_eq!(headings.len(), 4);
         assert_eq!(headings[0].line_number, 1);
         assert_eq!(headings[1].line_number, 2);
@@ -437,11 +748,11 @@ mod tests {
     #[test]
     fn test_headings_with_inline_formatting() {
         // Headings with bold, italic, code, and links preserved exactly
-        let md = r#"## **Bold** heading
+        let md = r"## **Bold** heading
 ### Heading with `code`
 #### Heading with *italic* text
-##### Mix **bold** and `code` and [link](url)"#;
-        let headings = extract_headings(md);
+##### Mix **bold** and `code` and [link](url)";
+        let headings = extract_headings(md, false);
         assert_eq!(headings.len(), 4);
         assert_eq!(headings[0].text, "## **Bold** heading");
         assert_eq!(headings[1].text, "### Heading with `code`");
@@ -455,17 +766,98 @@ mod tests {
     #[test]
     fn test_empty_document() {
         let md = "";
-        let headings = extract_headings(md);
+        let headings = extract_headings(md, false);
         assert_eq!(headings.len(), 0);
 
         let toc = generate_toc(md, md.len(), &TocConfig::default());
         assert!(toc.is_none());
     }
 
+    #[test]
+    fn test_find_section_boundaries_splits_on_matching_level() {
+        let md = "# Intro\nfirst section\n## Setup\nsecond section\n# Usage\nthird section";
+        let boundaries = find_section_boundaries(md, 1);
+
+        assert_eq!(boundaries.len(), 2);
+        assert_eq!(boundaries[0].heading.text, "# Intro");
+        assert_eq!(boundaries[0].start_byte, 0);
+        assert_eq!(boundaries[0].end_byte, md.find("# Usage").unwrap());
+        assert_eq!(
+            &md[boundaries[0].start_byte..boundaries[0].end_byte],
+            "# Intro\nfirst section\n## Setup\nsecond section\n"
+        );
+
+        assert_eq!(boundaries[1].heading.text, "# Usage");
+        assert_eq!(boundaries[1].start_byte, md.find("# Usage").unwrap());
+        assert_eq!(boundaries[1].end_byte, md.len());
+        assert_eq!(
+            &md[boundaries[1].start_byte..boundaries[1].end_byte],
+            "# Usage\nthird section"
+        );
+    }
+
+    #[test]
+    fn test_find_section_boundaries_deeper_level_keeps_subheadings() {
+        let md = "# Intro\nintro text\n## Setup\nsetup text\n## Config\nconfig text";
+        let boundaries = find_section_boundaries(md, 2);
+
+        assert_eq!(boundaries.len(), 3);
+        assert_eq!(
+            boundaries
+                .iter()
+                .map(|b| b.heading.text.as_str())
+                .collect::<Vec<_>>(),
+            vec!["# Intro", "## Setup", "## Config"]
+        );
+        assert_eq!(boundaries[2].end_byte, md.len());
+    }
+
+    #[test]
+    fn test_find_section_boundaries_ignores_headings_below_level() {
+        let md = "# Intro\n## Setup\n### Details\nbody\n## Config";
+        let boundaries = find_section_boundaries(md, 2);
+
+        // H3 "Details" is below the requested level and is not a boundary,
+        // so its body stays part of the enclosing "Setup" section.
+        assert_eq!(boundaries.len(), 3);
+        assert_eq!(
+            boundaries
+                .iter()
+                .map(|b| b.heading.text.as_str())
+                .collect::<Vec<_>>(),
+            vec!["# Intro", "## Setup", "## Config"]
+        );
+        assert!(md[boundaries[1].start_byte..boundaries[1].end_byte].contains("### Details"));
+    }
+
+    #[test]
+    fn test_find_section_boundaries_no_headings_is_empty() {
+        let md = "Just a paragraph, no headings at all.";
+        assert_eq!(find_section_boundaries(md, 6), vec![]);
+    }
+
+    #[test]
+    fn test_github_style_slug_lowercases_and_hyphenates() {
+        assert_eq!(github_style_slug("# Getting Started"), "getting-started");
+        assert_eq!(
+            github_style_slug("## API Reference (v2)"),
+            "api-reference-v2"
+        );
+    }
+
+    #[test]
+    fn test_github_style_slug_trims_stray_hyphens() {
+        assert_eq!(github_style_slug("### ---"), "");
+        assert_eq!(
+            github_style_slug("# Trailing punctuation!"),
+            "trailing-punctuation"
+        );
+    }
+
     #[test]
     fn test_document_with_no_headings() {
         let md = "Just some paragraph text.\n\nAnd another paragraph.";
-        let headings = extract_headings(md);
+        let headings = extract_headings(md, false);
         assert_eq!(headings.len(), 0);
 
         let toc = generate_toc(md, md.len(), &TocConfig::default());
@@ -478,30 +870,84 @@ mod tests {
             Heading {
                 level: 1,
                 line_number: 1,
+                start_byte: 0,
                 text: "# ".repeat(50),
             },
             Heading {
                 level: 2,
                 line_number: 2,
+                start_byte: 0,
                 text: "## ".repeat(50),
             },
             Heading {
                 level: 3,
                 line_number: 3,
+                start_byte: 0,
                 text: "### ".repeat(50),
             },
         ];
 
-        let result = find_optimal_level(&headings, 400);
+        let result = find_optimal_level(&headings, 400, DEFAULT_TOC_SEPARATOR, None, false, false);
         assert!(result.is_some());
         let (level, _toc) = result.unwrap();
         assert!(level >= 1);
     }
 
+    #[test]
+    fn test_max_level_cap_limits_depth_even_with_budget_to_spare() {
+        let headings = vec![
+            Heading {
+                level: 1,
+                line_number: 1,
+                start_byte: 0,
+                text: "# One".to_string(),
+            },
+            Heading {
+                level: 4,
+                line_number: 2,
+                start_byte: 0,
+                text: "#### Four".to_string(),
+            },
+        ];
+
+        let (level, toc) = find_optimal_level(
+            &headings,
+            4000,
+            DEFAULT_TOC_SEPARATOR,
+            Some(1),
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(level, 1);
+        assert!(!toc.contains("Four"));
+    }
+
+    #[test]
+    fn test_max_level_cap_above_document_depth_has_no_effect() {
+        let headings = vec![Heading {
+            level: 2,
+            line_number: 1,
+            start_byte: 0,
+            text: "## Only heading".to_string(),
+        }];
+
+        let (level, _toc) = find_optimal_level(
+            &headings,
+            4000,
+            DEFAULT_TOC_SEPARATOR,
+            Some(6),
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(level, 2);
+    }
+
     #[test]
     fn test_empty_headings() {
         let headings: Vec<Heading> = vec![];
-        let toc = render_toc(&headings, 3);
+        let toc = render_toc(&headings, 3, DEFAULT_TOC_SEPARATOR, false, false);
         assert_eq!(toc, "");
     }
 
@@ -511,16 +957,18 @@ mod tests {
             Heading {
                 level: 1,
                 line_number: 1,
+                start_byte: 0,
                 text: "# ".to_string() + &"x".repeat(10000),
             },
             Heading {
                 level: 1,
                 line_number: 2,
+                start_byte: 0,
                 text: "# ".to_string() + &"x".repeat(10000),
             },
         ];
 
-        let level = find_optimal_level(&headings, 10);
+        let level = find_optimal_level(&headings, 10, DEFAULT_TOC_SEPARATOR, None, false, false);
         assert!(level.is_none());
     }
 
@@ -536,10 +984,48 @@ mod tests {
         assert!(toc.is_none());
     }
 
+    #[test]
+    fn test_generate_toc_handles_h2_only_document_with_no_h1() {
+        // Some sites render the H1 outside the fetched content, so the
+        // document body starts at H2. Level 1 must be skipped (no
+        // headings at that level) rather than short-circuiting to `None`.
+        use std::fmt::Write;
+
+        let mut md = String::new();
+        for i in 1..=50 {
+            let filler = "content paragraph text ".repeat(20);
+            writeln!(md, "## Section {i}\n\n{filler}\n").unwrap();
+        }
+
+        let toc = generate_toc(&md, md.len(), &default_config()).unwrap();
+        assert!(toc.contains("Section 1"));
+        assert!(toc.contains("Section 50"));
+    }
+
+    #[test]
+    fn test_generate_toc_with_custom_separator() {
+        let md = format!(
+            "{}# Heading One\n{}# Heading Two\n{}",
+            "content\n".repeat(500),
+            "content\n".repeat(500),
+            "content\n".repeat(500)
+        );
+        let config = TocConfig {
+            separator: ": ".to_string(),
+            ..Default::default()
+        };
+
+        let toc = generate_toc(&md, md.len(), &config).unwrap();
+        assert!(toc.contains("Heading One"));
+        assert!(!toc.contains('→'));
+        let first_line = toc.lines().next().unwrap();
+        assert!(first_line.contains(": # Heading One"));
+    }
+
     #[test]
     fn test_deeply_nested_levels() {
         // Verify all 6 heading levels are recognized
-        let md = r#"# Main
+        let md = r"# Main
 
 ## Level 2
 
@@ -550,8 +1036,8 @@ mod tests {
 ##### Level 5
 
 ###### Level 6
-"#;
-        let headings = extract_headings(md);
+";
+        let headings = extract_headings(md, false);
         assert_eq!(headings.len(), 6);
         assert_eq!(headings[0].level, 1);
         assert_eq!(headings[1].level, 2);
@@ -561,6 +1047,65 @@ mod tests {
         assert_eq!(headings[5].level, 6);
     }
 
+    mod incremental {
+        use super::*;
+        use proptest::prelude::*;
+
+        #[test]
+        fn test_incremental_matches_full_reextraction_on_append() {
+            let old_md = "# Intro\n\nSome text.\n\n## Section One\n\nMore text.";
+            let new_md = format!("{old_md}\n\n## Section Two\n\nAppended text.");
+
+            let old_headings = extract_headings(old_md, false);
+            let incremental = extract_headings_incremental(&old_headings, old_md, &new_md, false);
+
+            assert_eq!(incremental, extract_headings(&new_md, false));
+        }
+
+        #[test]
+        fn test_incremental_falls_back_to_full_extraction_when_first_line_changes() {
+            let old_md = "# Intro\n\n## Section One\n\nText.";
+            let new_md = "# Different Intro\n\n## Section One\n\nText.";
+
+            let old_headings = extract_headings(old_md, false);
+            let incremental = extract_headings_incremental(&old_headings, old_md, new_md, false);
+
+            assert_eq!(incremental, extract_headings(new_md, false));
+        }
+
+        #[test]
+        fn test_incremental_reparses_a_heading_edited_mid_line() {
+            let old_md = "# Intro\n\n## Section One\n\nText.";
+            let new_md = "# Intro\n\n## Section One Edited\n\nText.";
+
+            let old_headings = extract_headings(old_md, false);
+            let incremental = extract_headings_incremental(&old_headings, old_md, new_md, false);
+
+            assert_eq!(incremental, extract_headings(new_md, false));
+        }
+
+        proptest! {
+            /// `extract_headings_incremental` must always agree with a full
+            /// `extract_headings(new_markdown, ...)` call, for any edit made
+            /// to `old_markdown` - appends, prepends, and arbitrary
+            /// insertions/deletions/replacements in the middle alike.
+            #[test]
+            fn proptest_incremental_matches_full_reextraction(
+                prefix in "(#{1,6} [A-Za-z0-9 ]{1,20}\n\n[A-Za-z0-9 ]{0,40}\n\n){1,5}",
+                suffix in "(#{1,6} [A-Za-z0-9 ]{1,20}\n\n[A-Za-z0-9 ]{0,40}\n\n){0,5}",
+                edit in "(#{1,6} [A-Za-z0-9 ]{1,20}\n\n[A-Za-z0-9 ]{0,40}\n\n){0,5}",
+            ) {
+                let old_md = format!("{prefix}{suffix}");
+                let new_md = format!("{prefix}{edit}{suffix}");
+
+                let old_headings = extract_headings(&old_md, false);
+                let incremental = extract_headings_incremental(&old_headings, &old_md, &new_md, false);
+
+                prop_assert_eq!(incremental, extract_headings(&new_md, false));
+            }
+        }
+    }
+
     // Snapshot tests with real-world documentation
     mod snapshots {
         use super::*;
@@ -600,6 +1145,50 @@ mod tests {
             insta::assert_snapshot!(toc.unwrap_or_default());
         }
 
+        #[test]
+        fn snapshot_vue_intro_indented() {
+            let md = include_str!("../test-fixtures/vue-intro.txt");
+            let config = TocConfig {
+                indent: true,
+                ..default_config()
+            };
+            let toc = generate_toc(md, md.len(), &config);
+            insta::assert_snapshot!(toc.unwrap_or_default());
+        }
+
+        #[test]
+        fn snapshot_vue_intro_numbered() {
+            let md = include_str!("../test-fixtures/vue-intro.txt");
+            let config = TocConfig {
+                numbering: true,
+                ..default_config()
+            };
+            let toc = generate_toc(md, md.len(), &config);
+            insta::assert_snapshot!(toc.unwrap_or_default());
+        }
+
+        #[test]
+        fn snapshot_python_tutorial_indented() {
+            let md = include_str!("../test-fixtures/python-tutorial.txt");
+            let config = TocConfig {
+                indent: true,
+                ..default_config()
+            };
+            let toc = generate_toc(md, md.len(), &config);
+            insta::assert_snapshot!(toc.unwrap_or_default());
+        }
+
+        #[test]
+        fn snapshot_python_tutorial_numbered() {
+            let md = include_str!("../test-fixtures/python-tutorial.txt");
+            let config = TocConfig {
+                numbering: true,
+                ..default_config()
+            };
+            let toc = generate_toc(md, md.len(), &config);
+            insta::assert_snapshot!(toc.unwrap_or_default());
+        }
+
         #[test]
         fn snapshot_vite_guide() {
             let md = include_str!("../test-fixtures/vite-guide.txt");
@@ -628,6 +1217,7 @@ mod tests {
             let config = TocConfig {
                 toc_budget: 4000,
                 full_content_threshold: 2000,
+                ..Default::default()
             };
             let toc = generate_toc(md, md.len(), &config);
             insta::assert_snapshot!(toc.unwrap_or_default());
@@ -640,6 +1230,7 @@ mod tests {
             let config = TocConfig {
                 toc_budget: 4000,
                 full_content_threshold: 1000,
+                ..Default::default()
             };
             let toc = generate_toc(md, md.len(), &config);
             insta::assert_snapshot!(toc.unwrap_or_default());
@@ -652,6 +1243,7 @@ mod tests {
             let config = TocConfig {
                 toc_budget: 4000,
                 full_content_threshold: 500,
+                ..Default::default()
             };
             let toc = generate_toc(md, md.len(), &config);
             insta::assert_snapshot!(toc.unwrap_or_default());
@@ -685,6 +1277,7 @@ mod tests {
             let config = TocConfig {
                 toc_budget: 4000,
                 full_content_threshold: 2000,
+                ..Default::default()
             };
             let toc = generate_toc(md, md.len(), &config);
             insta::assert_snapshot!(toc.unwrap_or_default());
@@ -697,6 +1290,7 @@ mod tests {
             let config = TocConfig {
                 toc_budget: 4000,
                 full_content_threshold: 2000,
+                ..Default::default()
             };
             let toc = generate_toc(md, md.len(), &config);
             insta::assert_snapshot!(toc.unwrap_or_default());
@@ -709,6 +1303,7 @@ mod tests {
             let config = TocConfig {
                 toc_budget: 4000,
                 full_content_threshold: 2000,
+                ..Default::default()
             };
             let toc = generate_toc(md, md.len(), &config);
             insta::assert_snapshot!(toc.unwrap_or_default());
@@ -725,6 +1320,7 @@ mod tests {
             let config = TocConfig {
                 toc_budget: 1500,
                 full_content_threshold: 8000,
+                ..Default::default()
             };
             let toc = generate_toc(md, md.len(), &config);
             insta::assert_snapshot!(toc.unwrap_or_default());
@@ -737,6 +1333,7 @@ mod tests {
             let config = TocConfig {
                 toc_budget: 10000,
                 full_content_threshold: 8000,
+                ..Default::default()
             };
             let toc = generate_toc(md, md.len(), &config);
             insta::assert_snapshot!(toc.unwrap_or_default());
@@ -749,6 +1346,7 @@ mod tests {
             let config = TocConfig {
                 toc_budget: 4000,
                 full_content_threshold: 2000,
+                ..Default::default()
             };
             let toc = generate_toc(md, md.len(), &config);
             insta::assert_snapshot!(toc.unwrap_or_default());
@@ -761,6 +1359,7 @@ mod tests {
             let config = TocConfig {
                 toc_budget: 50000,
                 full_content_threshold: 8000,
+                ..Default::default()
             };
             let toc = generate_toc(md, md.len(), &config);
             insta::assert_snapshot!(toc.unwrap_or_default());
@@ -773,6 +1372,7 @@ mod tests {
             let config = TocConfig {
                 toc_budget: 50000,
                 full_content_threshold: 8000,
+                ..Default::default()
             };
             let toc = generate_toc(md, md.len(), &config);
             insta::assert_snapshot!(toc.unwrap_or_default());
@@ -785,6 +1385,7 @@ mod tests {
             let config = TocConfig {
                 toc_budget: 300,
                 full_content_threshold: 2000,
+                ..Default::default()
             };
             let toc = generate_toc(md, md.len(), &config);
             insta::assert_snapshot!(toc.unwrap_or_default());
@@ -797,6 +1398,7 @@ mod tests {
             let config = TocConfig {
                 toc_budget: 4000,
                 full_content_threshold: 1000,
+                ..Default::default()
             };
             let toc = generate_toc(md, md.len(), &config);
             insta::assert_snapshot!(toc.unwrap_or_default());
@@ -807,8 +1409,9 @@ mod tests {
             // Convex full has H4/H5 nesting - test with budget allowing deeper levels
             let md = include_str!("../test-fixtures/convex-llms-full.txt");
             let config = TocConfig {
-                toc_budget: 100000,
+                toc_budget: 100_000,
                 full_content_threshold: 8000,
+                ..Default::default()
             };
             let toc = generate_toc(md, md.len(), &config);
             insta::assert_snapshot!(toc.unwrap_or_default());
@@ -853,10 +1456,12 @@ mod tests {
             let small_budget = TocConfig {
                 toc_budget: 500,
                 full_content_threshold: 2000,
+                ..Default::default()
             };
             let large_budget = TocConfig {
                 toc_budget: 10000,
                 full_content_threshold: 2000,
+                ..Default::default()
             };
 
             let toc_small = generate_toc(md, md.len(), &small_budget);
@@ -880,10 +1485,12 @@ mod tests {
             let low_threshold = TocConfig {
                 toc_budget: 1000,
                 full_content_threshold: 1000,
+                ..Default::default()
             };
             let high_threshold = TocConfig {
                 toc_budget: 1000,
-                full_content_threshold: 100000,
+                full_content_threshold: 100_000,
+                ..Default::default()
             };
 
             let toc_low = generate_toc(md, md.len(), &low_threshold);
@@ -900,6 +1507,7 @@ mod tests {
             let config = TocConfig {
                 toc_budget: 1000,
                 full_content_threshold: 0,
+                ..Default::default()
             };
 
             let toc = generate_toc(small_md, small_md.len(), &config);
@@ -913,6 +1521,7 @@ mod tests {
             let tiny_budget = TocConfig {
                 toc_budget: 10,
                 full_content_threshold: 2000,
+                ..Default::default()
             };
 
             let toc = generate_toc(md, md.len(), &tiny_budget);
@@ -927,6 +1536,7 @@ mod tests {
             let config = TocConfig::default();
             assert_eq!(config.toc_budget, DEFAULT_TOC_BUDGET);
             assert_eq!(config.full_content_threshold, DEFAULT_TOC_THRESHOLD);
+            assert_eq!(config.separator, DEFAULT_TOC_SEPARATOR);
         }
     }
 }
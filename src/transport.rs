@@ -0,0 +1,74 @@
+//! SSE/streamable-HTTP transport, offered as an alternative to the default
+//! stdio transport via `--sse`. See `Cli::sse`.
+
+use std::net::SocketAddr;
+
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::{Next, from_fn_with_state};
+use axum::response::{IntoResponse, Response};
+use rmcp::transport::sse_server::{SseServer, SseServerConfig};
+use rmcp::{RoleServer, Service};
+
+/// Returns an error if `bind` is not a loopback address and `bind_any` isn't
+/// set. Checked separately from binding so `main` can fail fast before doing
+/// any other startup work.
+pub fn check_bind_addr(bind: SocketAddr, bind_any: bool) -> std::io::Result<()> {
+    if bind_any || bind.ip().is_loopback() {
+        Ok(())
+    } else {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "refusing to bind SSE transport to non-localhost address {bind} without --bind-any"
+            ),
+        ))
+    }
+}
+
+/// Serves `service_provider` (invoked once per SSE connection, so the
+/// underlying `FetchServer` must be cheap to clone) over `listener` until the
+/// process exits or is cancelled. When `auth_token` is set, every request
+/// must carry a matching `Authorization: Bearer <token>` header.
+pub async fn serve_sse<S, F>(
+    listener: tokio::net::TcpListener,
+    auth_token: Option<String>,
+    service_provider: F,
+) -> std::io::Result<()>
+where
+    S: Service<RoleServer>,
+    F: Fn() -> S + Send + 'static,
+{
+    let config = SseServerConfig {
+        bind: listener.local_addr()?,
+        sse_path: "/sse".to_string(),
+        post_path: "/message".to_string(),
+        ct: tokio_util::sync::CancellationToken::new(),
+        sse_keep_alive: None,
+    };
+    let (sse_server, router) = SseServer::new(config);
+    let router = match auth_token {
+        Some(token) => router.layer(from_fn_with_state(token, require_bearer_token)),
+        None => router,
+    };
+
+    let ct = sse_server.with_service(service_provider);
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async move { ct.cancelled().await })
+        .await
+}
+
+async fn require_bearer_token(State(token): State<String>, req: Request, next: Next) -> Response {
+    let expected = format!("Bearer {token}");
+    let authorized = req
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|h| h == expected);
+
+    if authorized {
+        next.run(req).await
+    } else {
+        (StatusCode::UNAUTHORIZED, "missing or invalid bearer token").into_response()
+    }
+}
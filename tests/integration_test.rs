@@ -95,6 +95,2791 @@ fn test_fetch_svelte_docs() {
     assert!(svelte_path.exists(), "Cache directory should be created");
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_retries_on_empty_content() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    struct FlakyOnce {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl Respond for FlakyOnce {
+        fn respond(&self, _req: &Request) -> ResponseTemplate {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if call == 0 {
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/html")
+                    .set_body_string("")
+            } else {
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/html")
+                    .set_body_string(
+                        "<html><body><p>The cache has warmed up and this is the real documentation page content, now long enough to clear the minimum content length threshold.</p></body></html>",
+                    )
+            }
+        }
+    }
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/doc"))
+        .respond_with(FlakyOnce {
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        })
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/doc", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "include_content": true }
+        }
+    });
+
+    // Run the already-built binary directly (CARGO_BIN_EXE_...) rather than
+    // `cargo run`, since a `cargo run` rebuild can consume our piped stdin
+    // bytes before the server binary is even exec'd.
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        // Pace writes: the server reads line-by-line, and writing everything
+        // in one burst can race the initialize handshake on some platforms.
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    // The mock's first response is empty, triggering the retry's 2s delay;
+    // give the server time to finish before closing stdin (EOF shuts it down).
+    std::thread::sleep(std::time::Duration::from_secs(4));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("\"retried\":true"),
+        "expected a retried file in response: {stdout}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_ignores_empty_llms_txt_and_caches_html_conversion() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/doc"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "<html><body><p>This is the real documentation page content, long enough \
+             to clear the minimum content length threshold on its own.</p></body></html>",
+            "text/html",
+        ))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/doc/llms.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw("", "text/plain"))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/doc", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    child.wait_with_output().unwrap();
+
+    let cached_file = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| {
+            entry.path().is_file()
+                && entry.path().extension().is_none_or(|ext| ext != "meta")
+                && entry.path().file_name() != Some(std::ffi::OsStr::new(".gitignore"))
+        })
+        .map(|entry| entry.path().to_path_buf())
+        .expect("expected a cached markdown file");
+
+    let cached_content = fs::read_to_string(&cached_file).unwrap();
+    assert!(
+        cached_content.contains("real documentation page content"),
+        "expected the HTML conversion to be cached, got: {cached_content}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_demotes_tiny_variation_below_richer_md_variation() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/doc"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw("Loading...", "text/markdown"))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/doc.md"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "# Real Documentation\n\nThis is the actual documentation content for this page, \
+             long enough to clear the minimum content length threshold on its own, unlike the \
+             tiny placeholder served at the bare URL.",
+            "text/markdown",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/doc", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .arg("--min-content-chars")
+        .arg("100")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    // The bare URL's shell content stays below min_content_chars on every
+    // attempt, so it incurs the same-URL retry's 2s delay; give the server
+    // time to finish before closing stdin.
+    std::thread::sleep(std::time::Duration::from_secs(4));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let response: serde_json::Value = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find(|v| v["id"] == 1)
+        .unwrap_or_else(|| panic!("expected a response to the fetch call: {stdout}"));
+
+    let files = response["result"]["structuredContent"]["files"]
+        .as_array()
+        .expect("expected a files array");
+    assert_eq!(files.len(), 2, "expected both variations to be fetched");
+
+    assert!(
+        files[0]["source_url"]
+            .as_str()
+            .unwrap()
+            .ends_with("/doc.md"),
+        "expected the richer .md variation to be sorted first, got: {files:?}"
+    );
+    assert!(
+        files[0]["warning"].is_null(),
+        "the richer variation shouldn't carry a warning"
+    );
+    assert!(
+        files[1]["warning"]
+            .as_str()
+            .is_some_and(|w| w.contains("min_content_chars")),
+        "expected the tiny shell variation to be demoted with a min_content_chars warning, got: {files:?}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_concurrent_identical_fetches_coalesce_into_one_request() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/slow"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/html")
+                .set_body_string(
+                    "<html><body><p>This is the slow documentation page content, long enough to clear the minimum content length threshold on its own.</p></body></html>",
+                )
+                .set_delay(std::time::Duration::from_millis(500)),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/slow", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = |id: u64| {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": {
+                "name": "fetch",
+                "arguments": { "url": url }
+            }
+        })
+    };
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    // Fire two identical `fetch` calls back-to-back, with no delay between
+    // them, so the second one lands while the first is still in flight and
+    // should coalesce onto it rather than repeating the network request.
+    for id in [1, 2] {
+        stdin.write_all(call(id).to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+    }
+    std::thread::sleep(std::time::Duration::from_secs(2));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let responses: Vec<serde_json::Value> = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|v| v["id"] == 1 || v["id"] == 2)
+        .collect();
+    assert_eq!(
+        responses.len(),
+        2,
+        "expected a response to both calls: {stdout}"
+    );
+    for response in &responses {
+        let files = response["result"]["structuredContent"]["files"]
+            .as_array()
+            .unwrap_or_else(|| panic!("expected a files array in: {response}"));
+        assert_eq!(files.len(), 1);
+    }
+
+    // `.expect(1)` on the mock (checked when the server drops) already
+    // enforces this, but assert explicitly for a clearer failure message.
+    // Variation probing (llms.txt, .md, etc.) hits other paths on the same
+    // server, so only count requests for the exact URL under test.
+    let slow_requests = mock_server
+        .received_requests()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|req| req.url.path() == "/slow")
+        .count();
+    assert_eq!(
+        slow_requests, 1,
+        "expected the coalesced calls to hit the server only once"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_treats_persistently_empty_body_as_failed_variation() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/doc"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw("   \n", "text/html"))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/doc", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    // The body is always empty, so the empty-body retry's 2s delay fires
+    // too; give the server time to finish before closing stdin.
+    std::thread::sleep(std::time::Duration::from_secs(4));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("empty body"),
+        "expected an empty-body error in response: {stdout}"
+    );
+
+    let cached_file = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| {
+            entry.path().is_file()
+                && entry.path().extension().is_none_or(|ext| ext != "meta")
+                && entry.path().file_name() != Some(std::ffi::OsStr::new(".gitignore"))
+        });
+    assert!(
+        cached_file.is_none(),
+        "expected no file to be cached for a persistently empty body"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_recovers_content_from_javadoc_frameset() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    // A classic Javadoc-style frameset index: no body text of its own, just
+    // a nav frame and a content frame. Readability extraction leaves this
+    // near-empty, which is exactly the "suspiciously short" condition that
+    // should trigger frame recovery.
+    Mock::given(method("GET"))
+        .and(path("/index.html"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            r#"<html><head><title>API Docs</title></head>
+                    <frameset cols="20%,80%">
+                        <frame src="overview-frame.html" name="packageListFrame">
+                        <frame src="overview-summary.html" name="classFrame">
+                    </frameset>
+                    </html>"#,
+            "text/html",
+        ))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/overview-frame.html"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "<html><body><ul><li>com.example.widgets</li></ul></body></html>",
+            "text/html",
+        ))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/overview-summary.html"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "<html><body><h1>com.example.widgets</h1><p>This package provides the widget toolkit's public API, including the long-standing Widget and WidgetFactory interfaces documented below.</p></body></html>",
+            "text/html",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/index.html", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "include_content": true }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    // The frameset index is near-empty on its own, triggering the retry's
+    // 2s delay before frame recovery even runs.
+    std::thread::sleep(std::time::Duration::from_secs(4));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("widget toolkit's public API"),
+        "expected recovered content from the classFrame target: {stdout}"
+    );
+    assert!(
+        stdout.contains("overview-summary.html"),
+        "expected redirected_from to point at the recovered frame: {stdout}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_follows_pagination_and_concatenates_pages() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide/page-1"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            r#"<html><head><link rel="next" href="/guide/page-2"></head>
+                    <body><p>This is the first page of a two-part guide, long enough to clear the minimum content length threshold on its own.</p></body></html>"#,
+            "text/html",
+        ))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/guide/page-2"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "<html><body><p>This is the second and final page of the guide, with its own distinct closing paragraph of content.</p></body></html>",
+            "text/html",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide/page-1", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "include_content": true, "follow_pagination": true }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("first page of a two-part guide"),
+        "expected first page's content: {stdout}"
+    );
+    assert!(
+        stdout.contains("second and final page"),
+        "expected second page's content concatenated onto the first: {stdout}"
+    );
+    assert!(
+        stdout.contains("/guide/page-2"),
+        "expected pagination_urls to record the second page's URL: {stdout}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_respects_robots_txt_disallow() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/robots.txt"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /private/\n"),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/private/secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "<html><body><p>This content should never be fetched since robots.txt disallows it.</p></body></html>",
+            "text/html",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/private/secret", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "include_content": true, "respect_robots_txt": true }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("disallowed by robots.txt"),
+        "expected a robots.txt rejection error: {stdout}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_dry_run_reports_plan_without_downloading_bodies() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/robots.txt"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /private/\n"),
+        )
+        .mount(&mock_server)
+        .await;
+    // No mock for GET or HEAD on /private/secret itself: if `dry_run`
+    // downloaded a body (or the plan's robots check didn't actually run),
+    // wiremock would reject the unexpected request and the test would fail.
+    Mock::given(method("HEAD"))
+        .and(path("/private/secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw("", "text/html"))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/private/secret", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": {
+                "url": url,
+                "respect_robots_txt": true,
+                "dry_run": true,
+                "probe": true,
+            }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let response: serde_json::Value = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .find(|msg: &serde_json::Value| msg["id"] == 1)
+        .expect("expected a response to the fetch call");
+    let content = response["result"]["content"][0]["text"].as_str().unwrap();
+    let fetch_output: serde_json::Value = serde_json::from_str(content).unwrap();
+
+    assert!(
+        fetch_output["files"].as_array().unwrap().is_empty(),
+        "dry_run shouldn't write any files: {fetch_output}"
+    );
+    let plan = fetch_output["plan"].as_array().unwrap();
+    let entry = plan
+        .iter()
+        .find(|v| v["url"] == url)
+        .expect("expected a plan entry for the requested URL");
+    assert_eq!(entry["robots_allowed"], false);
+    assert_eq!(entry["cached_and_fresh"], false);
+    assert_eq!(entry["probe_status"], 200);
+    assert_eq!(entry["probe_content_type"], "text/html");
+    assert!(entry["predicted_path"].as_str().unwrap().contains("secret"));
+
+    assert!(
+        !cache_dir
+            .join(mock_server.address().to_string())
+            .join("private")
+            .exists(),
+        "dry_run shouldn't create any cache files on disk"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_follows_llms_txt_links() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/llms.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "# Docs\n\n- [Guide](/docs/guide.md)\n- [API](/docs/api.md)\n",
+            "text/plain",
+        ))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/docs/guide.md"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "This is the guide document linked from the llms.txt index.",
+            "text/markdown",
+        ))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/docs/api.md"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "This is the API reference document linked from the llms.txt index.",
+            "text/markdown",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/llms.txt", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "include_content": true, "follow_llms_txt": true }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("guide document linked from the llms.txt index"),
+        "expected the linked guide doc to be fetched and inlined: {stdout}"
+    );
+    assert!(
+        stdout.contains("API reference document linked from the llms.txt index"),
+        "expected the linked API doc to be fetched and inlined: {stdout}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_expand_dedups_variations_resolving_to_the_same_final_url() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    // Both chapters in the expanded range redirect to the same landing page,
+    // as happens when a site renumbers chapters but keeps old links alive.
+    for chapter in ["chapter-01", "chapter-02"] {
+        Mock::given(method("GET"))
+            .and(path(format!("/{chapter}")))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("Location", format!("{}/guide", mock_server.uri())),
+            )
+            .mount(&mock_server)
+            .await;
+    }
+    // The landing page includes a per-request visit counter, as a live
+    // analytics widget might, so the two redirected requests get byte-
+    // different bodies even though they're the same canonical page - only
+    // dedup keyed on the resolved final URL (not raw content) catches this.
+    let visits = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+    Mock::given(method("GET"))
+        .and(path("/guide"))
+        .respond_with(move |_: &wiremock::Request| {
+            let visit = visits.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            ResponseTemplate::new(200).set_body_raw(
+                format!(
+                    "<html><body><h1>Guide</h1><p>This guide walks through installing the \
+                     toolkit, configuring your first project, and deploying it to production \
+                     once you're happy with the result.</p><p>Visit {visit}</p></body></html>"
+                ),
+                "text/html",
+            )
+        })
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/chapter-{{01..02}}", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "expand": true, "normalize_urls": false }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("\"duplicate_of\""),
+        "expected the second redirected-to-the-same-page variation to be reported as a \
+         duplicate: {stdout}"
+    );
+
+    let written_files: Vec<_> = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_file() && entry.path().to_string_lossy().ends_with("index"))
+        .collect();
+    assert_eq!(
+        written_files.len(),
+        1,
+        "both chapter URLs resolve to the same final URL and should be written once: {stdout}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_deduplicates_near_identical_content() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    // `/stable` and `/v3.2` serve the same guide, as documentation sites
+    // commonly do; only a trailing exclamation point differs, a small
+    // fraction of this long a document.
+    let paragraphs: String = (0..20)
+        .map(|i| {
+            format!(
+                "<p>Paragraph {i} explains how the widget toolkit's configuration system lets \
+                 you override defaults for individual widgets without touching global state.</p>"
+            )
+        })
+        .collect();
+    let body = format!(
+        "<html><body><h1>Widget Guide</h1>{paragraphs}<p>Getting started with the widget \
+         toolkit requires installing the core package and configuring your first widget before \
+         anything else works as expected in this tutorial.</p></body></html>"
+    );
+    Mock::given(method("GET"))
+        .and(path("/stable/guide.html"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body.clone(), "text/html"))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v3.2/guide.html"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(body.replace("tutorial.", "tutorial!"), "text/html"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let stable_url = format!("{}/stable/guide.html", mock_server.uri());
+    let versioned_url = format!("{}/v3.2/guide.html", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call_stable = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": stable_url, "deduplicate_content": true }
+        }
+    });
+    let call_versioned = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": versioned_url, "deduplicate_content": true }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call_stable, call_versioned] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("\"duplicate_of\""),
+        "expected the versioned copy to be reported as a duplicate: {stdout}"
+    );
+    assert!(
+        stdout.contains("stable/guide.html"),
+        "expected duplicate_of to point at the already-cached stable copy: {stdout}"
+    );
+    let wrote_duplicate = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .any(|entry| entry.path().to_string_lossy().contains("v3.2"));
+    assert!(
+        !wrote_duplicate,
+        "the near-duplicate copy should not have been written to the cache"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_writes_meta_sidecar_with_response_headers() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide.html"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("etag", "\"abc123\"")
+                .insert_header("last-modified", "Wed, 21 Oct 2026 07:28:00 GMT")
+                .set_body_raw(
+                    "<html><body><h1>Guide</h1><p>This guide walks through installing the \
+                     toolkit, configuring your first project, and deploying it to production \
+                     once you're happy with the result.</p></body></html>",
+                    "text/html",
+                ),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide.html", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    child.wait_with_output().unwrap();
+
+    let meta_path = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| entry.path().to_string_lossy().ends_with(".meta"))
+        .map(|entry| entry.path().to_path_buf())
+        .expect("expected a .meta sidecar next to the cached file");
+
+    let meta: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(meta_path).unwrap()).unwrap();
+    assert_eq!(meta["url"], url);
+    assert_eq!(meta["etag"], "\"abc123\"");
+    assert_eq!(meta["last_modified"], "Wed, 21 Oct 2026 07:28:00 GMT");
+    assert!(meta["fetched_at_unix"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_normalizes_crlf_line_endings_in_cached_content() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide.md"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw("Line one.\r\nLine two.\r\nLine three.\r\n", "text/markdown"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide.md", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    child.wait_with_output().unwrap();
+
+    let content_path = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| {
+            let name = entry.path().to_string_lossy();
+            entry.path().is_file() && !name.ends_with(".meta") && !name.ends_with("index")
+        })
+        .map(|entry| entry.path().to_path_buf())
+        .expect("expected a cached content file");
+
+    let cached = fs::read_to_string(content_path).unwrap();
+    assert!(
+        !cached.contains('\r'),
+        "expected CRLF to be normalized to LF: {cached:?}"
+    );
+    assert!(cached.contains("Line one.\nLine two.\nLine three."));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_ensures_exactly_one_trailing_newline_in_cached_content() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/no-newline.md"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw("No trailing newline here.", "text/markdown"),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/many-newlines.md"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw("Several trailing newlines here.\n\n\n\n", "text/markdown"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let no_newline_url = format!("{}/no-newline.md", mock_server.uri());
+    let many_newlines_url = format!("{}/many-newlines.md", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call_no_newline = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": no_newline_url }
+        }
+    });
+    let call_many_newlines = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": many_newlines_url }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call_no_newline, call_many_newlines] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    child.wait_with_output().unwrap();
+
+    let content_files: Vec<_> = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.path().to_string_lossy();
+            entry.path().is_file()
+                && (name.contains("no-newline") || name.contains("many-newlines"))
+                && !name.ends_with(".meta")
+        })
+        .map(|entry| entry.path().to_path_buf())
+        .collect();
+    assert_eq!(content_files.len(), 2, "expected two cached content files");
+
+    for content_path in content_files {
+        let cached = fs::read_to_string(&content_path).unwrap();
+        assert!(
+            cached.ends_with('\n') && !cached.ends_with("\n\n"),
+            "expected exactly one trailing newline in {content_path:?}: {cached:?}"
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_normalizes_typography_when_requested_but_preserves_it_in_code_blocks() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let body = "\u{2018}Curly\u{2019} \u{201C}quotes\u{201D} and a non\u{00A0}breaking space.\n\n```\na\u{00A0}b\n```\n";
+    Mock::given(method("GET"))
+        .and(path("/typography.md"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/markdown"))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/typography.md", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "normalize_typography": true }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    child.wait_with_output().unwrap();
+
+    let content_path = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| {
+            let name = entry.path().to_string_lossy();
+            entry.path().is_file() && !name.ends_with(".meta") && !name.ends_with("index")
+        })
+        .map(|entry| entry.path().to_path_buf())
+        .expect("expected a cached content file");
+
+    let cached = fs::read_to_string(content_path).unwrap();
+    assert!(cached.contains("'Curly' \"quotes\" and a non breaking space."));
+    assert!(
+        cached.contains("a\u{00A0}b"),
+        "expected the NBSP inside the code block to survive: {cached:?}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_sends_accept_language_and_records_content_language() {
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide"))
+        .and(header("accept-language", "fr"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-language", "fr")
+                .set_body_raw(
+                    "<html><body><h1>Guide</h1><p>Ce guide explique comment installer \
+                     l'outil, configurer votre premier projet et le déployer en \
+                     production une fois que vous êtes satisfait du résultat.</p></body></html>",
+                    "text/html",
+                ),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "language": "fr" }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // The mock only matches (and thus only responds 200) when it sees the
+    // `Accept-Language: fr` header, so a cached file existing at all proves
+    // the header was sent; the response's `Content-Language` is asserted
+    // directly on the tool's JSON output.
+    assert!(
+        stdout.contains("\"content_language\":\"fr\""),
+        "expected content_language to be recorded from the response header: {stdout}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_caches_under_canonical_url() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            format!(
+                "<html><head><link rel=\"canonical\" href=\"{}/docs/guide\"></head><body>\
+                 <h1>Guide</h1><p>This guide walks through installing the toolkit, \
+                 configuring your first project, and deploying it to production once \
+                 you're happy with the result.</p></body></html>",
+                mock_server.uri()
+            ),
+            "text/html",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide?utm_source=newsletter", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let guide_path = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| entry.path().to_string_lossy().ends_with("guide"))
+        .map(|entry| entry.path().to_path_buf());
+
+    assert!(
+        guide_path.is_some(),
+        "expected the cached file to land under the canonical path 'docs/guide', not 'guide'"
+    );
+    let relative = guide_path
+        .unwrap()
+        .strip_prefix(&cache_dir)
+        .unwrap()
+        .to_string_lossy()
+        .replace('\\', "/");
+    assert!(
+        relative.contains("docs/guide"),
+        "expected canonical path 'docs/guide', got '{relative}'"
+    );
+
+    let response: serde_json::Value = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .find(|msg: &serde_json::Value| msg["id"] == 1)
+        .expect("expected a response to the fetch call");
+    let content = response["result"]["content"][0]["text"].as_str().unwrap();
+    let fetch_output: serde_json::Value = serde_json::from_str(content).unwrap();
+    let file_info = &fetch_output["files"][0];
+    assert_eq!(
+        file_info["canonical_url"],
+        format!("{}/docs/guide", mock_server.uri())
+    );
+    assert_eq!(file_info["source_url"], file_info["canonical_url"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_sends_post_body_and_content_type() {
+    use wiremock::matchers::{body_string, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .and(header("content-type", "application/graphql"))
+        .and(body_string("{ __schema { types { name } } }"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "application/json")
+                .set_body_string(
+                    r#"{"data":{"__schema":{"types":[{"name":"Query"},{"name":"Mutation"},{"name":"Subscription"},{"name":"String"},{"name":"Int"}]}}}"#,
+                ),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/graphql", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": {
+                "url": url,
+                "method": "POST",
+                "post_body": "{ __schema { types { name } } }",
+                "post_content_type": "application/graphql"
+            }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    child.wait_with_output().unwrap();
+
+    let cached_path = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| entry.path().to_string_lossy().contains("graphql"))
+        .map(|entry| entry.path().to_path_buf());
+
+    assert!(
+        cached_path.is_some(),
+        "expected a cached file for the POST response under the graphql path"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_chunks_document_into_per_heading_section_files() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide.html"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "<html><body><h1>Introduction</h1><p>This guide walks through installing the \
+             toolkit from scratch, covering every prerequisite along the way.</p>\
+             <h1>Configuration</h1><p>Once installed, configure your first project by \
+             editing the generated settings file to match your environment.</p>\
+             </body></html>",
+            "text/html",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide.html", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "chunk_by_heading": 1, "converter": "raw-html" }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    child.wait_with_output().unwrap();
+
+    let mut section_paths: Vec<_> = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.path().to_string_lossy();
+            name.contains(".section") && !name.ends_with(".meta")
+        })
+        .map(|entry| entry.path().to_path_buf())
+        .collect();
+    section_paths.sort();
+
+    assert_eq!(
+        section_paths.len(),
+        2,
+        "expected one section file per H1, got {section_paths:?}"
+    );
+    assert!(
+        section_paths[0]
+            .to_string_lossy()
+            .contains("section001-introduction"),
+        "expected the first section's filename to incorporate its heading slug: {:?}",
+        section_paths[0]
+    );
+    assert!(
+        section_paths[1]
+            .to_string_lossy()
+            .contains("section002-configuration"),
+        "expected the second section's filename to incorporate its heading slug: {:?}",
+        section_paths[1]
+    );
+
+    let first = fs::read_to_string(&section_paths[0]).unwrap();
+    let second = fs::read_to_string(&section_paths[1]).unwrap();
+    assert!(first.contains("Introduction"));
+    assert!(first.contains("every prerequisite"));
+    assert!(!first.contains("Configuration"));
+    assert!(second.contains("Configuration"));
+    assert!(second.contains("settings file"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_include_raw_html_writes_raw_file_alongside_converted_markdown() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide.html"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "<html><body><h1>Guide</h1><p>This guide walks through installing the toolkit, \
+             configuring your first project, and deploying it to production once you're \
+             happy with the result.</p></body></html>",
+            "text/html",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide.html", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "include_raw_html": true }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let response: serde_json::Value = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .find(|msg: &serde_json::Value| msg["id"] == 1)
+        .expect("expected a response to the fetch call");
+    let content = response["result"]["content"][0]["text"].as_str().unwrap();
+    let fetch_output: serde_json::Value = serde_json::from_str(content).unwrap();
+    let files = fetch_output["files"].as_array().unwrap();
+
+    assert_eq!(
+        files.len(),
+        2,
+        "expected converted + raw FileInfo entries: {files:?}"
+    );
+    let converted = files
+        .iter()
+        .find(|f| f["content_type"] == "html-converted")
+        .expect("expected an html-converted FileInfo");
+    let raw = files
+        .iter()
+        .find(|f| f["content_type"] == "html-raw")
+        .expect("expected an html-raw FileInfo");
+    assert_eq!(
+        raw["path"],
+        format!("{}.html", converted["path"].as_str().unwrap())
+    );
+    assert_eq!(converted["raw_html_path"], raw["path"]);
+
+    let raw_path = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| entry.path().to_string_lossy().ends_with("guide.html.html"))
+        .map(|entry| entry.path().to_path_buf())
+        .expect("expected the raw HTML file to be written to disk");
+    let raw_disk_content = fs::read_to_string(&raw_path).unwrap();
+    assert!(raw_disk_content.contains("<h1>Guide</h1>"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_with_keep_raw_then_reconvert_changes_markdown_without_network() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide.html"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "<html><body><h1>Guide</h1><p>This guide walks through installing the toolkit, \
+             configuring your first project, and deploying it to production once you're \
+             happy with the result.</p></body></html>",
+            "text/html",
+        ))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide.html", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let fetch_call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .arg("--keep-raw")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(initialize.to_string().as_bytes()).unwrap();
+    stdin.write_all(b"\n").unwrap();
+    stdin.write_all(initialized.to_string().as_bytes()).unwrap();
+    stdin.write_all(b"\n").unwrap();
+    stdin.write_all(fetch_call.to_string().as_bytes()).unwrap();
+    stdin.write_all(b"\n").unwrap();
+    stdin.flush().unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    let cached_path = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| entry.path().to_string_lossy().ends_with("guide.html"))
+        .map(|entry| entry.path().to_path_buf())
+        .expect("expected the converted file to be cached");
+    let raw_path = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| entry.path().to_string_lossy().ends_with(".raw.html"))
+        .map(|entry| entry.path().to_path_buf())
+        .expect("expected a .raw.html sidecar since --keep-raw was set");
+
+    let before = fs::read_to_string(&cached_path).unwrap();
+    let relative_cached_path = cached_path
+        .strip_prefix(&cache_dir)
+        .unwrap()
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    let reconvert_call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": {
+            "name": "reconvert",
+            "arguments": {
+                "path": relative_cached_path,
+                "converter": "raw-html"
+            }
+        }
+    });
+    stdin
+        .write_all(reconvert_call.to_string().as_bytes())
+        .unwrap();
+    stdin.write_all(b"\n").unwrap();
+    stdin.flush().unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let fetch_response: serde_json::Value = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find(|v| v["id"] == 1)
+        .unwrap_or_else(|| panic!("expected a response to the fetch call: {stdout}"));
+    let file_info = &fetch_response["result"]["structuredContent"]["files"][0];
+    let relative_path = file_info["relative_path"]
+        .as_str()
+        .expect("expected a relative_path alongside the absolute path");
+    let cache_dir_field = fetch_response["result"]["structuredContent"]["cache_dir"]
+        .as_str()
+        .expect("expected a cache_dir alongside the files list");
+    assert_eq!(
+        std::path::Path::new(cache_dir_field).join(relative_path),
+        cached_path,
+        "relative_path resolved against cache_dir should match the absolute path"
+    );
+
+    let after = fs::read_to_string(&cached_path).unwrap();
+    assert_ne!(
+        before, after,
+        "expected reconvert with a different converter to change the cached markdown"
+    );
+    assert!(raw_path.exists(), "raw sidecar should still be on disk");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_and_reconvert_produce_the_same_toc_for_identical_content() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide.html"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "<html><body><h1>Guide</h1><p>Intro text.</p>\
+             <h2>Installation</h2><p>Install text.</p>\
+             <h2>Configuration</h2><p>Configuration text.</p></body></html>",
+            "text/html",
+        ))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide.html", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let fetch_call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .arg("--keep-raw")
+        .arg("--toc-threshold")
+        .arg("0")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(initialize.to_string().as_bytes()).unwrap();
+    stdin.write_all(b"\n").unwrap();
+    stdin.write_all(initialized.to_string().as_bytes()).unwrap();
+    stdin.write_all(b"\n").unwrap();
+    stdin.write_all(fetch_call.to_string().as_bytes()).unwrap();
+    stdin.write_all(b"\n").unwrap();
+    stdin.flush().unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    let cached_path = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| entry.path().to_string_lossy().ends_with("guide.html"))
+        .map(|entry| entry.path().to_path_buf())
+        .expect("expected the converted file to be cached");
+    let relative_cached_path = cached_path
+        .strip_prefix(&cache_dir)
+        .unwrap()
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    let reconvert_call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": {
+            "name": "reconvert",
+            "arguments": { "path": relative_cached_path }
+        }
+    });
+    stdin
+        .write_all(reconvert_call.to_string().as_bytes())
+        .unwrap();
+    stdin.write_all(b"\n").unwrap();
+    stdin.flush().unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let fetch_response: serde_json::Value = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find(|v| v["id"] == 1)
+        .unwrap_or_else(|| panic!("expected a response to the fetch call: {stdout}"));
+    let fetch_toc = fetch_response["result"]["structuredContent"]["files"][0]["table_of_contents"]
+        .as_str()
+        .expect("expected fetch to report a table_of_contents for a multi-heading document")
+        .to_string();
+
+    let reconvert_response: serde_json::Value = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find(|v| v["id"] == 2)
+        .unwrap_or_else(|| panic!("expected a response to the reconvert call: {stdout}"));
+    let reconvert_toc = reconvert_response["result"]["structuredContent"]["table_of_contents"]
+        .as_str()
+        .expect("expected reconvert to report a table_of_contents for a multi-heading document")
+        .to_string();
+
+    assert_eq!(
+        fetch_toc, reconvert_toc,
+        "fetch and reconvert should generate identical ToCs for identical content via the shared toc_for helper"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_persists_cookies_across_calls() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    struct GatedByCookie;
+
+    impl Respond for GatedByCookie {
+        fn respond(&self, req: &Request) -> ResponseTemplate {
+            let has_session_cookie = req
+                .headers
+                .get("cookie")
+                .is_some_and(|v| v.to_str().unwrap_or_default().contains("session=abc123"));
+            if has_session_cookie {
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/html")
+                    .set_body_string(
+                        "<html><body><p>This is the gated documentation page content, visible only once the session cookie has been sent back by the client on a later request.</p></body></html>",
+                    )
+            } else {
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/html")
+                    .insert_header("set-cookie", "session=abc123; Path=/")
+                    .set_body_string(
+                        "<html><body><p>This is the login landing page content, long enough to clear the minimum content length threshold on its own.</p></body></html>",
+                    )
+            }
+        }
+    }
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/login"))
+        .respond_with(GatedByCookie)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/gated"))
+        .respond_with(GatedByCookie)
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let login_url = format!("{}/login", mock_server.uri());
+    let gated_url = format!("{}/gated", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let login_call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": login_url, "include_content": true }
+        }
+    });
+    let gated_call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": gated_url, "include_content": true }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, login_call, gated_call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("gated documentation page content"),
+        "expected the gated page to be served once the session cookie was sent back: {stdout}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_docs_requires_cookie_set_by_root() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    struct RootSetsCookie;
+    impl Respond for RootSetsCookie {
+        fn respond(&self, _req: &Request) -> ResponseTemplate {
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/html")
+                .insert_header("set-cookie", "session=root123; Path=/")
+                .set_body_string(
+                    "<html><body><p>This is the site root page content, long enough to clear the minimum content length threshold on its own.</p></body></html>",
+                )
+        }
+    }
+
+    struct DocsGatedByCookie;
+    impl Respond for DocsGatedByCookie {
+        fn respond(&self, req: &Request) -> ResponseTemplate {
+            let has_session_cookie = req
+                .headers
+                .get("cookie")
+                .is_some_and(|v| v.to_str().unwrap_or_default().contains("session=root123"));
+            if has_session_cookie {
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/html")
+                    .set_body_string(
+                        "<html><body><p>This is the docs page content, visible only once the root-set session cookie has been sent back.</p></body></html>",
+                    )
+            } else {
+                ResponseTemplate::new(403).set_body_string("Forbidden")
+            }
+        }
+    }
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(RootSetsCookie)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/docs"))
+        .respond_with(DocsGatedByCookie)
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let root_url = mock_server.uri();
+    let docs_url = format!("{}/docs", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let root_call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": root_url, "include_content": true }
+        }
+    });
+    let docs_call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": docs_url, "include_content": true }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, root_call, docs_call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("docs page content"),
+        "expected /docs to be served once the root-set session cookie was sent back: {stdout}"
+    );
+}
+
+/// `ToC` line numbers are only useful if they point at the line actually
+/// written to disk, which means generation must happen after every
+/// transform that could shift line counts, not just after conversion. This
+/// re-reads the cached file and checks every `ToC` entry against it, so a
+/// future transform inserted between conversion and the write would be
+/// caught here instead of silently producing stale line numbers.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_toc_line_numbers_match_the_file_written_to_disk() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide.html"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "<html><body>\
+             <h1>Introduction</h1><p>This guide walks through installing the toolkit from \
+             scratch, covering every prerequisite along the way in careful detail so nothing \
+             is missed.</p>\
+             <h1>Configuration</h1><p>Once installed, configure your first project by editing \
+             the generated settings file to match your environment and your team's \
+             conventions.</p>\
+             <h1>Deployment</h1><p>Finally, deploy the project to production following the \
+             recommended rollout steps and monitoring setup described here.</p>\
+             </body></html>",
+            "text/html",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide.html", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "converter": "raw-html" }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .arg("--toc-threshold")
+        .arg("50")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let response: serde_json::Value = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find(|v| v["id"] == 1)
+        .unwrap_or_else(|| panic!("expected a response to the fetch call: {stdout}"));
+
+    let file_info = &response["result"]["structuredContent"]["files"][0];
+    let toc = file_info["table_of_contents"]
+        .as_str()
+        .expect("expected a table_of_contents for a document past --toc-threshold");
+    let path = file_info["path"].as_str().unwrap();
+    let written = std::fs::read_to_string(path).unwrap();
+    let written_lines: Vec<&str> = written.lines().collect();
+
+    assert!(!toc.is_empty(), "expected at least one ToC entry");
+    for entry in toc.lines() {
+        let (line_number, heading_text) = entry
+            .split_once('→')
+            .expect("expected a 'line_number→heading' ToC entry");
+        let line_number: usize = line_number.trim().parse().unwrap();
+        let actual_line = written_lines.get(line_number - 1).unwrap_or_else(|| {
+            panic!("ToC pointed at line {line_number}, but the written file only has {} lines: {written}", written_lines.len())
+        });
+        assert!(
+            actual_line.starts_with(heading_text),
+            "line {line_number} of the written file is {actual_line:?}, expected it to start with {heading_text:?}"
+        );
+    }
+}
+
+/// `max_heading_depth` caps `find_optimal_level`'s own depth selection, so a
+/// document with budget to spare for every heading still gets a shallow
+/// `ToC` when the caller asks for one.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_max_heading_depth_caps_toc_to_requested_level() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide.html"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "<html><body>\
+             <h1>Introduction</h1><p>This guide walks through installing the toolkit from \
+             scratch, covering every prerequisite along the way in careful detail so nothing \
+             is missed.</p>\
+             <h2>Prerequisites</h2><p>A short list of things to install first, described here \
+             in enough detail to avoid any ambiguity about versions or platforms.</p>\
+             <h4>Optional extras</h4><p>A deep subsection that a caller asking for a coarse \
+             table of contents would rather not see cluttering up the overview.</p>\
+             </body></html>",
+            "text/html",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide.html", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "converter": "raw-html", "max_heading_depth": 1 }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .arg("--toc-threshold")
+        .arg("50")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let response: serde_json::Value = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find(|v| v["id"] == 1)
+        .unwrap_or_else(|| panic!("expected a response to the fetch call: {stdout}"));
+
+    let file_info = &response["result"]["structuredContent"]["files"][0];
+    let toc = file_info["table_of_contents"]
+        .as_str()
+        .expect("expected a table_of_contents for a document past --toc-threshold");
+
+    assert!(
+        toc.contains("Introduction"),
+        "expected the H1 in the ToC: {toc}"
+    );
+    assert!(
+        !toc.contains("Prerequisites") && !toc.contains("Optional extras"),
+        "expected H2/H4 headings to be excluded by max_heading_depth: {toc}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_reports_nonzero_timing_for_delayed_variation() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/slow"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/html")
+                .set_body_string(
+                    "<html><body><p>This is the slow documentation page content, long enough to clear the minimum content length threshold on its own.</p></body></html>",
+                )
+                .set_delay(std::time::Duration::from_millis(300)),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/slow", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "include_timings": true }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("\"timings\""),
+        "expected timings to be reported: {stdout}"
+    );
+
+    let fetch_ms_values: Vec<u64> = stdout
+        .split("\"fetch_ms\":")
+        .skip(1)
+        .filter_map(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+        .filter_map(|digits| digits.parse().ok())
+        .collect();
+    assert!(
+        fetch_ms_values.iter().any(|&ms| ms > 0),
+        "expected at least one nonzero fetch_ms in response: {stdout}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_rejects_misspelled_argument_with_helpful_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": "https://example.com/doc", "includeContent": true }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("includeContent"),
+        "expected the error to name the misspelled field: {stdout}"
+    );
+}
+
 #[test]
 fn test_url_variations_logic() {
     // Test that .md URLs don't generate variations
@@ -103,30 +2888,987 @@ fn test_url_variations_logic() {
     assert_eq!(variations.len(), 1);
     assert_eq!(variations[0], md_url);
 
-    // Test that regular URLs generate all variations
-    let regular_url = "https://example.com/page";
-    let variations = get_url_variations(regular_url);
-    assert_eq!(variations.len(), 5);
-    assert_eq!(variations[0], "https://example.com/page");
-    assert_eq!(variations[1], "https://example.com/page.md");
-    assert_eq!(variations[2], "https://example.com/page/index.md");
-    assert_eq!(variations[3], "https://example.com/page/llms.txt");
-    assert_eq!(variations[4], "https://example.com/page/llms-full.txt");
+    // Test that regular URLs generate all variations
+    let regular_url = "https://example.com/page";
+    let variations = get_url_variations(regular_url);
+    assert_eq!(variations.len(), 5);
+    assert_eq!(variations[0], "https://example.com/page");
+    assert_eq!(variations[1], "https://example.com/page.md");
+    assert_eq!(variations[2], "https://example.com/page/index.md");
+    assert_eq!(variations[3], "https://example.com/page/llms.txt");
+    assert_eq!(variations[4], "https://example.com/page/llms-full.txt");
+}
+
+fn get_url_variations(url: &str) -> Vec<String> {
+    let mut variations = vec![url.to_string()];
+
+    let url_lower = url.to_lowercase();
+    if url_lower.ends_with(".md") || url_lower.ends_with(".txt") {
+        return variations;
+    }
+
+    let base = url.trim_end_matches('/');
+    variations.push(format!("{}.md", base));
+    variations.push(format!("{}/index.md", base));
+    variations.push(format!("{}/llms.txt", base));
+    variations.push(format!("{}/llms-full.txt", base));
+
+    variations
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sse_transport_serves_tools_list_and_fetch() {
+    use rmcp::ServiceExt;
+    use rmcp::model::CallToolRequestParam;
+    use rmcp::transport::SseClientTransport;
+    use rmcp::transport::sse_client::SseClientConfig;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/doc"))
+        .respond_with(ResponseTemplate::new(200).insert_header("content-type", "text/html").set_body_string(
+            "<html><body><p>This is documentation content served over the SSE transport, long enough to clear the minimum content length threshold.</p></body></html>",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let auth_token = "integration-test-token";
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .arg("--sse")
+        .arg("127.0.0.1:0")
+        .arg("--auth-token")
+        .arg(auth_token)
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let stderr = child.stderr.take().unwrap();
+    let mut reader = std::io::BufReader::new(stderr);
+    let mut line = String::new();
+    std::io::BufRead::read_line(&mut reader, &mut line).unwrap();
+    let addr = line
+        .trim()
+        .strip_prefix("listening for SSE connections on ")
+        .expect("expected the server to log its bound address")
+        .to_string();
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(
+        reqwest::header::AUTHORIZATION,
+        format!("Bearer {auth_token}").parse().unwrap(),
+    );
+    let http_client = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap();
+
+    let transport = SseClientTransport::start_with_client(
+        http_client,
+        SseClientConfig {
+            sse_endpoint: format!("http://{addr}/sse").into(),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let client = ().serve(transport).await.unwrap();
+
+    let tools = client.list_all_tools().await.unwrap();
+    assert!(
+        tools.iter().any(|t| t.name == "fetch"),
+        "expected a `fetch` tool in: {tools:?}"
+    );
+
+    let url = format!("{}/doc", mock_server.uri());
+    let mut arguments = serde_json::Map::new();
+    arguments.insert("url".to_string(), serde_json::json!(url));
+    arguments.insert("include_content".to_string(), serde_json::json!(true));
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "fetch".into(),
+            arguments: Some(arguments),
+        })
+        .await
+        .unwrap();
+    let result_text = serde_json::to_string(&result).unwrap();
+    assert!(
+        result_text.contains("documentation content"),
+        "expected fetched content in tool result: {result_text}"
+    );
+
+    client.cancel().await.unwrap();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_emits_a_logging_notification() {
+    use rmcp::ServiceExt;
+    use rmcp::handler::client::ClientHandler;
+    use rmcp::model::{CallToolRequestParam, LoggingMessageNotificationParam};
+    use rmcp::service::{NotificationContext, RoleClient};
+    use rmcp::transport::SseClientTransport;
+    use rmcp::transport::sse_client::SseClientConfig;
+    use std::sync::{Arc, Mutex};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    // Records every `notifications/message` the server sends us during the
+    // `fetch` call below, so we can assert at least one arrived.
+    #[derive(Clone, Default)]
+    struct LogCapture {
+        messages: Arc<Mutex<Vec<LoggingMessageNotificationParam>>>,
+    }
+
+    impl ClientHandler for LogCapture {
+        fn on_logging_message(
+            &self,
+            params: LoggingMessageNotificationParam,
+            _context: NotificationContext<RoleClient>,
+        ) -> impl std::future::Future<Output = ()> + Send + '_ {
+            self.messages.lock().unwrap().push(params);
+            std::future::ready(())
+        }
+    }
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/doc"))
+        .respond_with(ResponseTemplate::new(200).insert_header("content-type", "text/html").set_body_string(
+            "<html><body><p>Documentation content long enough to clear the minimum content length threshold for this logging notification test.</p></body></html>",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let auth_token = "integration-test-token";
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .arg("--sse")
+        .arg("127.0.0.1:0")
+        .arg("--auth-token")
+        .arg(auth_token)
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let stderr = child.stderr.take().unwrap();
+    let mut reader = std::io::BufReader::new(stderr);
+    let mut line = String::new();
+    std::io::BufRead::read_line(&mut reader, &mut line).unwrap();
+    let addr = line
+        .trim()
+        .strip_prefix("listening for SSE connections on ")
+        .expect("expected the server to log its bound address")
+        .to_string();
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(
+        reqwest::header::AUTHORIZATION,
+        format!("Bearer {auth_token}").parse().unwrap(),
+    );
+    let http_client = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap();
+
+    let transport = SseClientTransport::start_with_client(
+        http_client,
+        SseClientConfig {
+            sse_endpoint: format!("http://{addr}/sse").into(),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let capture = LogCapture::default();
+    let client = capture.clone().serve(transport).await.unwrap();
+
+    let url = format!("{}/doc", mock_server.uri());
+    let mut arguments = serde_json::Map::new();
+    arguments.insert("url".to_string(), serde_json::json!(url));
+    arguments.insert("include_content".to_string(), serde_json::json!(true));
+    client
+        .call_tool(CallToolRequestParam {
+            name: "fetch".into(),
+            arguments: Some(arguments),
+        })
+        .await
+        .unwrap();
+
+    let received = capture.messages.lock().unwrap().len();
+    assert!(
+        received > 0,
+        "expected at least one logging notification during fetch, got none"
+    );
+
+    client.cancel().await.unwrap();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_refresh_cache_updates_only_the_changed_file() {
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    // `changed` gets a new body and ETag on its conditional re-fetch; the
+    // other two report 304 Not Modified, as an unchanged origin would.
+    for (slug, body) in [
+        (
+            "unchanged-1",
+            "<html><body><h1>One</h1><p>The first page, which never changes, long enough to clear the minimum content length threshold.</p></body></html>",
+        ),
+        (
+            "unchanged-2",
+            "<html><body><h1>Two</h1><p>The second page, which never changes, long enough to clear the minimum content length threshold.</p></body></html>",
+        ),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/{slug}")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("ETag", "\"v1\"")
+                    .set_body_raw(body, "text/html"),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/{slug}")))
+            .and(header("If-None-Match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .mount(&mock_server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/changed"))
+        .respond_with(ResponseTemplate::new(200).insert_header("ETag", "\"v1\"").set_body_raw(
+            "<html><body><h1>Changed</h1><p>This page will be edited before the refresh, long enough to clear the minimum content length threshold.</p></body></html>",
+            "text/html",
+        ))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/changed"))
+        .and(header("If-None-Match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(200).insert_header("ETag", "\"v2\"").set_body_raw(
+            "<html><body><h1>Changed</h1><p>This is the updated body returned after the ETag no longer matches, long enough to clear the threshold.</p></body></html>",
+            "text/html",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(initialize.to_string().as_bytes()).unwrap();
+    stdin.write_all(b"\n").unwrap();
+    stdin.write_all(initialized.to_string().as_bytes()).unwrap();
+    stdin.write_all(b"\n").unwrap();
+
+    for (id, slug) in [(1, "unchanged-1"), (2, "unchanged-2"), (3, "changed")] {
+        let fetch_call = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": {
+                "name": "fetch",
+                "arguments": { "url": format!("{}/{slug}", mock_server.uri()) }
+            }
+        });
+        stdin.write_all(fetch_call.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+
+    let refresh_call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 4,
+        "method": "tools/call",
+        "params": {
+            "name": "refresh_cache",
+            "arguments": {}
+        }
+    });
+    stdin
+        .write_all(refresh_call.to_string().as_bytes())
+        .unwrap();
+    stdin.write_all(b"\n").unwrap();
+    stdin.flush().unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let refresh_response: serde_json::Value = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find(|v| v["id"] == 4)
+        .unwrap_or_else(|| panic!("expected a response to the refresh_cache call: {stdout}"));
+    let structured = &refresh_response["result"]["structuredContent"];
+    assert_eq!(
+        structured["checked"], 3,
+        "expected all three cached files to be checked: {stdout}"
+    );
+    assert_eq!(
+        structured["unchanged"], 2,
+        "expected the two untouched pages to report unchanged: {stdout}"
+    );
+    assert_eq!(
+        structured["updated"], 1,
+        "expected only the edited page to report updated: {stdout}"
+    );
+    assert_eq!(structured["failed"], 0, "expected no failures: {stdout}");
+    assert!(
+        structured["updates"][0]["url"]
+            .as_str()
+            .unwrap()
+            .ends_with("/changed"),
+        "expected the update to be reported for the changed page: {stdout}"
+    );
+
+    let changed_path = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| {
+            entry.path().is_file() && entry.path().to_string_lossy().ends_with("changed/index")
+        })
+        .map(|entry| entry.path().to_path_buf())
+        .expect("expected the changed page's cached file on disk");
+    let refreshed_body = fs::read_to_string(&changed_path).unwrap();
+    assert!(
+        refreshed_body.contains("updated body"),
+        "expected the cached file to be rewritten with the new body: {refreshed_body}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_short_connect_timeout_fails_fast_against_an_unroutable_address() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    // 10.255.255.1 is a non-routable address commonly used to simulate a
+    // dead host: the connect phase never completes, so only the connect
+    // timeout (not the much longer total request timeout) bounds how long
+    // this takes to fail.
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": "http://10.255.255.1/guide" }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .env("LLMS_FETCH_CONNECT_TIMEOUT_SECS", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+    }
+    drop(stdin);
+
+    let started = std::time::Instant::now();
+    let output = child.wait_with_output().unwrap();
+    let elapsed = started.elapsed();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        elapsed < std::time::Duration::from_secs(10),
+        "expected the connect timeout to bound the failure well under the 30s total request \
+         timeout, took {elapsed:?}: {stdout}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_cache_subdir_nests_writes_under_the_subdirectory() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide.md"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw("# Guide\n\nBody text.", "text/markdown"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide.md", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "cache_subdir": "job-42" }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    child.wait_with_output().unwrap();
+
+    let content_path = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| {
+            let name = entry.path().to_string_lossy();
+            entry.path().is_file() && !name.ends_with(".meta") && !name.ends_with(".gitignore")
+        })
+        .map(|entry| entry.path().to_path_buf())
+        .expect("expected a cached content file");
+
+    assert!(
+        content_path.starts_with(cache_dir.join("job-42")),
+        "expected the cached file to live under the cache_subdir: {content_path:?}"
+    );
 }
 
-fn get_url_variations(url: &str) -> Vec<String> {
-    let mut variations = vec![url.to_string()];
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_max_write_bytes_aborts_and_removes_written_files() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
-    let url_lower = url.to_lowercase();
-    if url_lower.ends_with(".md") || url_lower.ends_with(".txt") {
-        return variations;
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide.md"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "# Guide\n\nThis body is long enough to exceed a tiny max_write_bytes cap.",
+            "text/markdown",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide.md", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "max_write_bytes": 5 }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("max_write_bytes"),
+        "expected the error to mention max_write_bytes: {stdout}"
+    );
+
+    let leftover_content_file = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| {
+            let name = entry.path().to_string_lossy();
+            entry.path().is_file() && !name.ends_with(".gitignore")
+        });
+    assert!(
+        leftover_content_file.is_none(),
+        "expected no files left behind after the quota breach: {leftover_content_file:?}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_http_version_http1_still_succeeds() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide.md"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw("# Guide\n\nBody text.", "text/markdown"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide.md", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "http_version": "http1" }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
     }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    child.wait_with_output().unwrap();
 
-    let base = url.trim_end_matches('/');
-    variations.push(format!("{}.md", base));
-    variations.push(format!("{}/index.md", base));
-    variations.push(format!("{}/llms.txt", base));
-    variations.push(format!("{}/llms-full.txt", base));
+    let content_path = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| {
+            let name = entry.path().to_string_lossy();
+            entry.path().is_file() && !name.ends_with(".meta") && !name.ends_with(".gitignore")
+        });
+    assert!(
+        content_path.is_some(),
+        "expected a cached content file when pinning http1 against an HTTP/1.1 server"
+    );
+}
 
-    variations
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_probe_skips_get_when_head_reports_oversized_content_length() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(path("/guide.md"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/markdown")
+                .insert_header("content-length", "1000000"),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/guide.md"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw("# Guide\n\nBody text.", "text/markdown"),
+        )
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide.md", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "probe": true, "max_write_bytes": 1000 }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("skipped") || stdout.contains("max_write_bytes"),
+        "expected the response to report the probed-and-skipped variation: {stdout}"
+    );
+
+    let content_path = walkdir::WalkDir::new(&cache_dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| {
+            let name = entry.path().to_string_lossy();
+            entry.path().is_file() && !name.ends_with(".meta") && !name.ends_with(".gitignore")
+        });
+    assert!(
+        content_path.is_none(),
+        "expected no GET to have been made (mock asserts 0 GET calls on drop), so no content file: {content_path:?}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_sends_custom_headers_overriding_accept() {
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/guide"))
+        .and(header("x-api-version", "2026-01-01"))
+        .and(header("accept", "application/json"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "<html><body><h1>Guide</h1><p>A reasonably long paragraph of guide \
+             content so the converter doesn't treat this page as too thin to cache.</p></body></html>",
+            "text/html",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/guide", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": {
+                "url": url,
+                "custom_headers": { "X-Api-Version": "2026-01-01", "Accept": "application/json" }
+            }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // The mock only matches (and thus only responds 200) when it sees both
+    // the custom `X-Api-Version` header and an `Accept` overridden away from
+    // the tool's default, so a cached file existing at all proves both.
+    assert!(
+        stdout.contains("\"content_type\""),
+        "expected a successful fetch using the custom headers: {stdout}"
+    );
+}
+
+#[tokio::test]
+async fn test_fetch_rejects_header_injection_in_custom_headers() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": {
+                "url": "https://example.com/guide",
+                "custom_headers": { "X-Bad\r\nInjected": "value" }
+            }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(1));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("not a valid header name"),
+        "expected an invalid_params error for the header-injection attempt: {stdout}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fetch_detects_redirect_loop_on_trailing_slash_bounce() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    // A host that 301s the non-slash form to the slashed form and the
+    // slashed form right back, forever.
+    Mock::given(method("GET"))
+        .and(path("/docs"))
+        .respond_with(
+            ResponseTemplate::new(301)
+                .insert_header("Location", format!("{}/docs/", mock_server.uri())),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/docs/"))
+        .respond_with(
+            ResponseTemplate::new(301)
+                .insert_header("Location", format!("{}/docs", mock_server.uri())),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache_dir = temp_dir.path().to_path_buf();
+    let url = format!("{}/docs", mock_server.uri());
+
+    let initialize = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+        }
+    });
+    let initialized = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    });
+    let call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "fetch",
+            "arguments": { "url": url, "include_content": true }
+        }
+    });
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_llms-fetch-mcp"))
+        .arg(cache_dir.to_str().unwrap())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = child.stdin.take().unwrap();
+    for message in [initialize, initialized, call] {
+        stdin.write_all(message.to_string().as_bytes()).unwrap();
+        stdin.write_all(b"\n").unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    std::thread::sleep(std::time::Duration::from_secs(2));
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("redirect loop detected"),
+        "expected the bouncing redirect to be surfaced as a distinct loop error: {stdout}"
+    );
 }